@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use range::VersionReq;
 
 /// A single range set combining a number of ranges with an or (`||`).
@@ -32,10 +32,11 @@
 //!
 //! assert_eq!(Some(Ok(Token::AlphaNumeric("foo"))), l.next());
 //! assert_eq!(Some(Ok(Token::Whitespace(3, 4))), l.next());
-//! assert_eq!(Some(Err(Error::UnexpectedChar('/'))), l.next());
+//! assert_eq!(Some(Err(Error::UnexpectedChar('/', 4))), l.next());
 //! ```
 
-use std::str;
+use core::fmt;
+use core::str;
 use self::Token::*;
 use self::Error::*;
 
@@ -65,7 +66,7 @@ macro_rules! scan_while {
 }
 
 /// Semver tokens.
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Token<'input> {
     /// `=`
     Eq,
@@ -81,6 +82,8 @@ pub enum Token<'input> {
     Caret,
     /// '~`
     Tilde,
+    /// `~>`
+    Pessimistic,
     /// '*`
     Star,
     /// `.`
@@ -104,31 +107,57 @@ pub enum Token<'input> {
 impl<'input> Token<'input> {
     /// Check if the current token is a whitespace token.
     pub fn is_whitespace(&self) -> bool {
-        match *self {
-            Whitespace(..) => true,
-            _ => false,
-        }
+        matches!(*self, Whitespace(..))
+    }
+
+    /// Check if the current token is an operator token, like `>=` or `^`.
+    pub fn is_operator(&self) -> bool {
+        matches!(*self, Eq | Gt | Lt | LtEq | GtEq | Caret | Tilde | Pessimistic)
     }
 
     /// Check if the current token is a wildcard token.
     pub fn is_wildcard(&self) -> bool {
+        matches!(*self, Star | AlphaNumeric("X") | AlphaNumeric("x"))
+    }
+}
+
+/// Writes the token as it appears in the input, with any whitespace written as a single space.
+impl<'input> fmt::Display for Token<'input> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            Star |
-            AlphaNumeric("X") |
-            AlphaNumeric("x") => true,
-            _ => false,
+            Eq => f.write_str("="),
+            Gt => f.write_str(">"),
+            Lt => f.write_str("<"),
+            LtEq => f.write_str("<="),
+            GtEq => f.write_str(">="),
+            Caret => f.write_str("^"),
+            Tilde => f.write_str("~"),
+            Pessimistic => f.write_str("~>"),
+            Star => f.write_str("*"),
+            Dot => f.write_str("."),
+            Comma => f.write_str(","),
+            Hyphen => f.write_str("-"),
+            Plus => f.write_str("+"),
+            Or => f.write_str("||"),
+            Whitespace(..) => f.write_str(" "),
+            Numeric(number) => number.fmt(f),
+            AlphaNumeric(component) => f.write_str(component),
         }
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Error {
-    /// Unexpected character.
-    UnexpectedChar(char),
+    /// Unexpected character, and its byte offset in the input.
+    UnexpectedChar(char, usize),
+    /// A control character other than the whitespace `\t`, `\n` and `\r`, like NUL or DEL, and
+    /// its byte offset in the input. These usually come from binary data fed to the lexer by
+    /// accident.
+    IllegalCharacter(char, usize),
 }
 
 /// Lexer for semver tokens belonging to a range.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Lexer<'input> {
     input: &'input str,
     chars: str::CharIndices<'input>,
@@ -139,19 +168,25 @@ pub struct Lexer<'input> {
 
 impl<'input> Lexer<'input> {
     /// Construct a new lexer for the given input.
-    pub fn new(input: &str) -> Lexer {
+    pub fn new(input: &str) -> Lexer<'_> {
         let mut chars = input.char_indices();
         let c1 = chars.next();
         let c2 = chars.next();
 
         Lexer {
-            input: input,
-            chars: chars,
-            c1: c1,
-            c2: c2,
+            input,
+            chars,
+            c1,
+            c2,
         }
     }
 
+    /// Byte offset of the next character to be lexed, or the length of the input if all of it
+    /// has been consumed.
+    pub fn position(&self) -> usize {
+        self.c1.map(|(idx, _)| idx).unwrap_or_else(|| self.input.len())
+    }
+
     /// Shift all lookahead storage by one.
     fn step(&mut self) {
         self.c1 = self.c2;
@@ -180,8 +215,10 @@ impl<'input> Lexer<'input> {
     ///
     /// A component can either be an alphanumeric or numeric.
     /// Does not permit leading zeroes if numeric.
+    /// Only ASCII digits and letters are part of a component, other unicode digits are rejected
+    /// as unexpected characters.
     fn component(&mut self, start: usize) -> Result<Token<'input>, Error> {
-        let end = scan_while!(self, start, '0'...'9' | 'A'...'Z' | 'a'...'z');
+        let end = scan_while!(self, start, '0'..='9' | 'A'..='Z' | 'a'..='z');
         let input = &self.input[start..end];
 
         let mut it = input.chars();
@@ -212,52 +249,52 @@ impl<'input> Iterator for Lexer<'input> {
     type Item = Result<Token<'input>, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            // two subsequent char tokens.
-            if let Some((_, a, b)) = self.two() {
-                let two = match (a, b) {
-                    ('<', '=') => Some(LtEq),
-                    ('>', '=') => Some(GtEq),
-                    ('|', '|') => Some(Or),
-                    _ => None,
-                };
-
-                if let Some(two) = two {
-                    self.step_n(2);
-                    return Some(Ok(two));
-                }
+        // two subsequent char tokens.
+        if let Some((_, a, b)) = self.two() {
+            let two = match (a, b) {
+                ('<', '=') => Some(LtEq),
+                ('>', '=') => Some(GtEq),
+                ('~', '>') => Some(Pessimistic),
+                ('|', '|') => Some(Or),
+                _ => None,
+            };
+
+            if let Some(two) = two {
+                self.step_n(2);
+                return Some(Ok(two));
             }
+        }
 
-            // single char and start of numeric tokens.
-            if let Some((start, c)) = self.one() {
-                let tok = match c {
-                    ' ' | '\t' | '\n' | '\r' => {
-                        self.step();
-                        return Some(self.whitespace(start));
-                    }
-                    '=' => Eq,
-                    '>' => Gt,
-                    '<' => Lt,
-                    '^' => Caret,
-                    '~' => Tilde,
-                    '*' => Star,
-                    '.' => Dot,
-                    ',' => Comma,
-                    '-' => Hyphen,
-                    '+' => Plus,
-                    '0'...'9' | 'a'...'z' | 'A'...'Z' => {
-                        self.step();
-                        return Some(self.component(start));
-                    }
-                    c => return Some(Err(UnexpectedChar(c))),
-                };
-
-                self.step();
-                return Some(Ok(tok));
+        // single char and start of numeric tokens.
+        if let Some((start, c)) = self.one() {
+            let tok = match c {
+                ' ' | '\t' | '\n' | '\r' => {
+                    self.step();
+                    return Some(self.whitespace(start));
+                }
+                '=' => Eq,
+                '>' => Gt,
+                '<' => Lt,
+                '^' => Caret,
+                '~' => Tilde,
+                '*' => Star,
+                '.' => Dot,
+                ',' => Comma,
+                '-' => Hyphen,
+                '+' => Plus,
+                '0'..='9' | 'a'..='z' | 'A'..='Z' => {
+                    self.step();
+                    return Some(self.component(start));
+                }
+                c if c.is_control() => return Some(Err(IllegalCharacter(c, start))),
+                c => return Some(Err(UnexpectedChar(c, start))),
             };
 
-            return None;
-        }
+            self.step();
+            return Some(Ok(tok));
+        };
+
+        None
     }
 }
 
@@ -265,14 +302,14 @@ impl<'input> Iterator for Lexer<'input> {
 mod tests {
     use super::*;
 
-    fn lex(input: &str) -> Vec<Token> {
+    fn lex(input: &str) -> Vec<Token<'_>> {
         Lexer::new(input).map(Result::unwrap).collect::<Vec<_>>()
     }
 
     #[test]
     pub fn simple_tokens() {
         assert_eq!(
-            lex("=><<=>=^~*.,-+||"),
+            lex("=><<=>=^~*.,-+||~>"),
             vec![
                 Eq,
                 Gt,
@@ -287,6 +324,7 @@ mod tests {
                 Hyphen,
                 Plus,
                 Or,
+                Pessimistic,
             ]
         );
     }
@@ -316,6 +354,15 @@ mod tests {
     }
 
     #[test]
+    pub fn display() {
+        let tokens = lex(">=1.2.3-alpha1 , ~>2||*");
+        let text: Vec<_> = tokens.iter().map(ToString::to_string).collect();
+
+        assert_eq!(">=1.2.3-alpha1 , ~>2||*", text.concat());
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
     pub fn is_wildcard() {
         assert_eq!(Star.is_wildcard(), true);
         assert_eq!(AlphaNumeric("x").is_wildcard(), true);
@@ -323,6 +370,36 @@ mod tests {
         assert_eq!(AlphaNumeric("other").is_wildcard(), false);
     }
 
+    #[test]
+    pub fn is_operator() {
+        assert!(lex("=><<=>=^~").iter().all(Token::is_operator));
+        assert!(!lex("*.,-+||1a ").iter().any(Token::is_operator));
+    }
+
+    #[test]
+    pub fn position() {
+        let mut l = Lexer::new("foo >=1");
+
+        assert_eq!(0, l.position());
+        l.next();
+        assert_eq!(3, l.position());
+        l.next();
+        assert_eq!(4, l.position());
+        l.next();
+        assert_eq!(6, l.position());
+        l.next();
+        assert_eq!(7, l.position());
+        assert_eq!(None, l.next());
+    }
+
+    #[test]
+    pub fn unexpected_char() {
+        assert_eq!(
+            Some(Err(UnexpectedChar('/', 2))),
+            Lexer::new("1 / 4").nth(2)
+        );
+    }
+
     #[test]
     pub fn empty() {
         assert_eq!(lex(""), vec![]);
@@ -342,4 +419,22 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    pub fn unicode_digits() {
+        assert_eq!(Some(Err(UnexpectedChar('\u{0661}', 0))), Lexer::new("\u{0661}.0.0").next());
+        assert_eq!(Some(Err(UnexpectedChar('\u{ff11}', 2))), Lexer::new("1.\u{ff11}").nth(2));
+        assert_eq!(Some(Err(UnexpectedChar('\u{00b2}', 1))), Lexer::new("1\u{00b2}").nth(1));
+    }
+
+    #[test]
+    pub fn illegal_character() {
+        assert_eq!(Some(Err(IllegalCharacter('\0', 0))), Lexer::new("\0").next());
+        assert_eq!(Some(Err(IllegalCharacter('\u{7f}', 2))), Lexer::new("1.\u{7f}").nth(2));
+        assert_eq!(Some(Err(IllegalCharacter('\u{1b}', 1))), Lexer::new("1\u{1b}").nth(1));
+        assert_eq!(Some(Err(IllegalCharacter('\u{85}', 0))), Lexer::new("\u{85}").next());
+
+        // tabs are whitespace, not illegal.
+        assert_eq!(lex("1\t2"), vec![Numeric(1), Whitespace(1, 2), Numeric(2)]);
+    }
 }
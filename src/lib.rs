@@ -21,20 +21,15 @@
 //!         minor: Some(0),
 //!         patch: Some(0),
 //!         pre: Vec::new(),
+//!         build: Vec::new(),
+//!         raw: String::from("1.0.0"),
 //!     },
 //!     r.predicates[0]
 //! );
 //!
 //! let m = version::parse("1.0.0")?;
 //! for p in &r.predicates {
-//!     match p.op {
-//!         range::Op::Compatible => {
-//!             assert_eq!(p.major, m.major);
-//!         }
-//!         _ => {
-//!             unimplemented!();
-//!         }
-//!     }
+//!     assert!(p.matches(&m));
 //! }
 //! # Ok(())
 //! # }
@@ -47,7 +42,19 @@
 //! [`range::Predicate`]: ./range/struct.Predicate.html
 //! [`range::VersionReq`]: ./range/struct.VersionReq.html
 
-#![doc(html_root_url = "https://docs.rs/semver-parser/0.8.0")]
+#![doc(html_root_url = "https://docs.rs/semver-parser/0.9.0")]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(any(feature = "std", test))]
+extern crate core;
+#[macro_use]
+extern crate alloc;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "semver-compat")]
+extern crate semver;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
 
 pub mod version;
 pub mod comparator;
@@ -29,6 +29,8 @@
 //!     minor: Some(0),
 //!     patch: None,
 //!     pre: vec![],
+//!     build: vec![],
+//!     raw: String::from("^1.0"),
 //! })), p.predicate());
 //!
 //! let mut p = Parser::new("^*").expect("a broken parser");
@@ -40,9 +42,15 @@ use lexer::{self, Lexer, Token};
 use self::Error::*;
 use range::{Predicate, Op, VersionReq, WildcardVersion};
 use comparator::Comparator;
-use version::{Version, Identifier};
-use std::mem;
-use std::fmt;
+use version::{Version, Identifier, InternedIdentifier, InternedVersion, Interner, VersionSpans};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::mem;
+use core::fmt;
+use core::ops::Range;
+use core::str;
+#[cfg(feature = "std")]
+use std::error;
 
 /// Evaluate if parser contains the given pattern as a separator, surrounded by whitespace.
 macro_rules! has_ws_separator {
@@ -62,20 +70,57 @@ macro_rules! has_ws_separator {
     }}
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Error<'input> {
     /// Needed more tokens for parsing, but none are available.
     UnexpectedEnd,
-    /// Unexpected token.
-    UnexpectedToken(Token<'input>),
+    /// Unexpected token, and the byte offset in the input where it starts.
+    UnexpectedToken(Token<'input>, usize),
     /// An error occurred in the lexer.
     Lexer(lexer::Error),
-    /// More input available.
-    MoreInput(Vec<Token<'input>>),
-    /// Encountered empty predicate in a set of predicates.
-    EmptyPredicate,
+    /// More input available, starting at the given byte offset.
+    MoreInput(Vec<Token<'input>>, usize),
+    /// Encountered empty predicate in a set of predicates, and the byte offset in the input of the
+    /// comma without a predicate before or after it.
+    EmptyPredicate(usize),
     /// Encountered an empty range.
     EmptyRange,
+    /// Numeric component or prerelease identifier with a leading zero, like `01`, and the byte
+    /// offset in the input where it starts.
+    LeadingZero(&'input str, usize),
+    /// An operator directly followed by another one, like `>= >=`, and the byte offset in the
+    /// input where the second one starts.
+    UnexpectedOperator(Token<'input>, Token<'input>, usize),
+    /// Empty prerelease or build identifier, like in `1.0.0-alpha..1` or `1.0.0+`, and the byte
+    /// offset in the input where it was expected.
+    EmptyIdentifier(usize),
+    /// One of the [`Limits`] of the parser was exceeded, and the byte offset in the input where
+    /// that happened.
+    ///
+    /// [`Limits`]: ./struct.Limits.html
+    LimitExceeded(Limit, usize),
+    /// A `<` or `>` operator applied to a bare wildcard, like `<*`, which could never match, and
+    /// the byte offset in the input where the wildcard starts.
+    WildcardAfterOperator(Op, usize),
+}
+
+impl<'input> Error<'input> {
+    /// Byte offset in the input where the error was encountered, if known.
+    pub fn position(&self) -> Option<usize> {
+        match *self {
+            UnexpectedToken(_, position) |
+            MoreInput(_, position) |
+            LeadingZero(_, position) |
+            UnexpectedOperator(_, _, position) |
+            EmptyPredicate(position) |
+            EmptyIdentifier(position) |
+            LimitExceeded(_, position) |
+            WildcardAfterOperator(_, position) |
+            Lexer(lexer::Error::UnexpectedChar(_, position)) |
+            Lexer(lexer::Error::IllegalCharacter(_, position)) => Some(position),
+            _ => None,
+        }
+    }
 }
 
 impl<'input> From<lexer::Error> for Error<'input> {
@@ -84,17 +129,39 @@ impl<'input> From<lexer::Error> for Error<'input> {
     }
 }
 
+/// Invalid UTF-8 is reported as an unexpected [`char::REPLACEMENT_CHARACTER`] at the offset of
+/// the first invalid byte, since semver input is ASCII anyway.
+impl<'input> From<str::Utf8Error> for Error<'input> {
+    fn from(value: str::Utf8Error) -> Self {
+        Error::Lexer(lexer::Error::UnexpectedChar(
+            char::REPLACEMENT_CHARACTER,
+            value.valid_up_to(),
+        ))
+    }
+}
+
 impl<'input> fmt::Display for Error<'input> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         use self::Error::*;
 
         match *self {
             UnexpectedEnd => write!(fmt, "expected more input"),
-            UnexpectedToken(ref token) => write!(fmt, "encountered unexpected token: {:?}", token),
+            UnexpectedToken(ref token, _) => write!(fmt, "encountered unexpected token: {:?}", token),
             Lexer(ref error) => write!(fmt, "lexer error: {:?}", error),
-            MoreInput(ref tokens) => write!(fmt, "expected end of input, but got: {:?}", tokens),
-            EmptyPredicate => write!(fmt, "encountered empty predicate"),
+            MoreInput(ref tokens, _) => write!(fmt, "expected end of input, but got: {:?}", tokens),
+            EmptyPredicate(_) => write!(fmt, "encountered empty predicate"),
             EmptyRange => write!(fmt, "encountered empty range"),
+            UnexpectedOperator(ref first, ref second, _) => {
+                write!(fmt, "encountered operator {:?} after operator {:?}", second, first)
+            }
+            EmptyIdentifier(_) => write!(fmt, "encountered empty identifier"),
+            LimitExceeded(ref limit, _) => write!(fmt, "exceeded limit on {}", limit),
+            WildcardAfterOperator(ref op, _) => {
+                write!(fmt, "operator {} applied to a wildcard can never match", op)
+            }
+            LeadingZero(ref number, _) => {
+                write!(fmt, "numeric identifier with leading zero: {}", number)
+            }
         }
     }
 }
@@ -106,18 +173,213 @@ impl<'input> From<Error<'input>> for String {
     }
 }
 
+#[cfg(feature = "std")]
+impl<'input> error::Error for Error<'input> {}
+
+/// An owned parse error, which does not borrow from the input.
+///
+/// Unlike [`Error`], a `ParseError` can outlive the string it was produced from, which makes it
+/// suitable for returning from [`FromStr`] implementations and for propagating with `?` through
+/// `Box<dyn Error>`.
+///
+/// # Examples
+///
+/// ```rust
+/// use semver_parser::parser::ParseError;
+/// use semver_parser::range;
+///
+/// let error = {
+///     let input = String::from(">== 0.0.2");
+///     ParseError::from(range::parse(&input).unwrap_err())
+/// };
+///
+/// assert_eq!(Some("="), error.text());
+/// assert_eq!(
+///     "encountered operator Eq after operator GtEq, offending input: `=`",
+///     error.to_string()
+/// );
+/// ```
+///
+/// [`Error`]: ./enum.Error.html
+/// [`FromStr`]: https://doc.rust-lang.org/std/str/trait.FromStr.html
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ParseError {
+    message: String,
+    position: Option<usize>,
+    text: Option<String>,
+}
+
+impl ParseError {
+    /// Description of the error, like `Display` but without the offending part of the input.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Byte offset in the input where the error was encountered, if known.
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+
+    /// The offending part of the input, like `=` in `>== 1.0`, if the error points at one.
+    ///
+    /// Whitespace in it is written as a single space.
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+}
+
+impl<'input> From<Error<'input>> for ParseError {
+    fn from(value: Error<'input>) -> Self {
+        let text = match value {
+            UnexpectedToken(ref token, _) | UnexpectedOperator(_, ref token, _) => {
+                Some(token.to_string())
+            }
+            MoreInput(ref tokens, _) => Some(tokens.iter().map(ToString::to_string).collect()),
+            LeadingZero(number, _) => Some(number.to_string()),
+            Lexer(lexer::Error::UnexpectedChar(c, _)) |
+            Lexer(lexer::Error::IllegalCharacter(c, _)) => Some(c.to_string()),
+            _ => None,
+        };
+
+        ParseError {
+            message: value.to_string(),
+            position: value.position(),
+            text,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(&self.message)?;
+
+        match self.text {
+            Some(ref text) => write!(fmt, ", offending input: `{}`", text),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ParseError {}
+
+/// impl for backwards compatibility.
+impl From<ParseError> for String {
+    fn from(value: ParseError) -> Self {
+        value.to_string()
+    }
+}
+
+/// Limits on the size of the input a [`Parser`] accepts, to bound the work and allocations done
+/// for hostile input.
+///
+/// The defaults are generous enough for any real version or requirement.
+///
+/// # Examples
+///
+/// ```rust
+/// use semver_parser::parser::{Error, Limit, Limits, Parser};
+///
+/// let limits = Limits {
+///     max_predicates: 2,
+///     ..Limits::default()
+/// };
+///
+/// let mut p = Parser::with_limits(">=1.0.0, <2.0.0, <1.5.0", limits).expect("a broken parser");
+///
+/// assert_eq!(Err(Error::LimitExceeded(Limit::Predicates, 17)), p.range());
+/// ```
+///
+/// [`Parser`]: ./struct.Parser.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Limits {
+    /// Maximum length of the input in bytes, `65536` by default.
+    pub max_input_length: usize,
+    /// Maximum number of predicates in a range, counting all groups separated by `||`, `1024`
+    /// by default.
+    pub max_predicates: usize,
+    /// Maximum number of dot-separated identifiers in a prerelease or in build metadata, `1024`
+    /// by default.
+    pub max_identifiers: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            max_input_length: 1 << 16,
+            max_predicates: 1024,
+            max_identifiers: 1024,
+        }
+    }
+}
+
+/// The kind of limit reported by [`Error::LimitExceeded`].
+///
+/// [`Error::LimitExceeded`]: ./enum.Error.html#variant.LimitExceeded
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Limit {
+    /// [`Limits::max_input_length`](./struct.Limits.html#structfield.max_input_length).
+    InputLength,
+    /// [`Limits::max_predicates`](./struct.Limits.html#structfield.max_predicates).
+    Predicates,
+    /// [`Limits::max_identifiers`](./struct.Limits.html#structfield.max_identifiers).
+    Identifiers,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Limit::InputLength => write!(fmt, "input length"),
+            Limit::Predicates => write!(fmt, "number of predicates"),
+            Limit::Identifiers => write!(fmt, "number of identifiers"),
+        }
+    }
+}
+
 /// A recursive-descent parser for parsing version requirements.
+#[derive(Clone)]
 pub struct Parser<'input> {
+    /// The input being parsed.
+    input: &'input str,
     /// Source of token.
     lexer: Lexer<'input>,
     /// Lookaehead.
     c1: Option<Token<'input>>,
+    /// Byte offset where the lookahead token starts.
+    position: usize,
+    /// Limits on the size of the input.
+    limits: Limits,
+    /// Number of predicates parsed so far.
+    predicates: usize,
+    /// Whether a bare `*` is parsed as a `WildcardVersion::Major` predicate.
+    explicit_major_wildcard: bool,
+    /// Whether empty comma-separated predicates are skipped instead of rejected.
+    relaxed: bool,
+    /// Whether a prerelease or build metadata requires all three version components.
+    strict: bool,
 }
 
 impl<'input> Parser<'input> {
-    /// Construct a new parser for the given input.
+    /// Construct a new parser for the given input, with the default [`Limits`].
+    ///
+    /// [`Limits`]: ./struct.Limits.html
     pub fn new(input: &'input str) -> Result<Parser<'input>, Error<'input>> {
+        Parser::with_limits(input, Limits::default())
+    }
+
+    /// Construct a new parser for the given input, with the given [`Limits`].
+    ///
+    /// [`Limits`]: ./struct.Limits.html
+    pub fn with_limits(
+        input: &'input str,
+        limits: Limits,
+    ) -> Result<Parser<'input>, Error<'input>> {
+        if input.len() > limits.max_input_length {
+            return Err(LimitExceeded(Limit::InputLength, limits.max_input_length));
+        }
+
         let mut lexer = Lexer::new(input);
+        let position = lexer.position();
 
         let c1 = if let Some(c1) = lexer.next() {
             Some(c1?)
@@ -126,21 +388,60 @@ impl<'input> Parser<'input> {
         };
 
         Ok(Parser {
-            lexer: lexer,
-            c1: c1,
+            input,
+            lexer,
+            c1,
+            position,
+            limits,
+            predicates: 0,
+            explicit_major_wildcard: false,
+            relaxed: false,
+            strict: false,
         })
     }
 
+    /// Parse a bare `*` as a predicate with [`WildcardVersion::Major`], instead of leaving it
+    /// out of the predicates like by default.
+    ///
+    /// [`WildcardVersion::Major`]: ../range/enum.WildcardVersion.html#variant.Major
+    pub fn set_explicit_major_wildcard(&mut self, explicit: bool) {
+        self.explicit_major_wildcard = explicit;
+    }
+
+    /// Skip empty comma-separated predicates and leading whitespace in ranges, like in
+    /// `>=1.0.0, , <2.0.0,`, instead of failing with `Error::EmptyPredicate`.
+    pub fn set_relaxed(&mut self, relaxed: bool) {
+        self.relaxed = relaxed;
+    }
+
+    /// Reject a prerelease or build metadata on a predicate with left out components, like
+    /// `0-Foo` or `1.2-rc`, with `Error::UnexpectedToken` for the `-` or `+`.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Skip whitespace and empty predicates in relaxed mode.
+    fn skip_empty_predicates(&mut self) -> Result<(), Error<'input>> {
+        if self.relaxed {
+            while has_ws_separator!(self, Some(&Token::Comma)) {}
+        }
+
+        Ok(())
+    }
+
     /// Pop one token.
     #[inline(always)]
     fn pop(&mut self) -> Result<Token<'input>, Error<'input>> {
+        let position = self.lexer.position();
+
         let c1 = if let Some(c1) = self.lexer.next() {
             Some(c1?)
         } else {
             None
         };
 
-        mem::replace(&mut self.c1, c1).ok_or_else(|| UnexpectedEnd)
+        self.position = position;
+        mem::replace(&mut self.c1, c1).ok_or(UnexpectedEnd)
     }
 
     /// Peek one token.
@@ -149,6 +450,17 @@ impl<'input> Parser<'input> {
         self.c1.as_ref()
     }
 
+    /// The part of the input which has not been parsed yet, starting with the next token.
+    pub fn remaining(&self) -> &'input str {
+        &self.input[self.position..]
+    }
+
+    /// Byte offset of the next token to be parsed, or the length of the input if all of it has
+    /// been consumed.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
     /// Skip whitespace if present.
     fn skip_whitespace(&mut self) -> Result<(), Error<'input>> {
         match self.peek() {
@@ -157,44 +469,75 @@ impl<'input> Parser<'input> {
         }
     }
 
-    /// Parse an optional comma separator, then if that is present a predicate.
+    /// Parse an optional comma or whitespace separator, then if that is present a predicate.
+    ///
+    /// Like, `, <2.0.0` or ` <2.0.0`.
+    ///
+    /// Any whitespace on either side of the comma is skipped, so `1.0 ,2.0` and `1.0,  2.0` are the
+    /// same as `1.0, 2.0`.
     pub fn comma_predicate(&mut self) -> Result<Option<Predicate>, Error<'input>> {
+        self.skip_whitespace()?;
+        let position = self.position;
         let has_comma = has_ws_separator!(self, Some(&Token::Comma));
 
+        if has_comma {
+            self.skip_empty_predicates()?;
+        }
+
+        // catch consecutive commas, like `>1.0.0, , <2.0.0`.
+        if has_comma && self.peek() == Some(&Token::Comma) {
+            return Err(EmptyPredicate(position));
+        }
+
         if let Some(predicate) = self.predicate()? {
             Ok(Some(predicate))
-        } else if has_comma {
-            Err(EmptyPredicate)
+        } else if has_comma && !self.relaxed {
+            Err(EmptyPredicate(position))
         } else {
             Ok(None)
         }
     }
 
-    /// Parse an optional or separator `||`, then if that is present a range.
-    fn or_range(&mut self) -> Result<Option<VersionReq>, Error<'input>> {
+    /// Parse an optional or separator `||`, then if that is present a set of predicates.
+    fn or_predicates(&mut self) -> Result<Option<Vec<Predicate>>, Error<'input>> {
         if !has_ws_separator!(self, Some(&Token::Or)) {
             return Ok(None);
         }
 
-        Ok(Some(self.range()?))
+        self.skip_empty_predicates()?;
+
+        match self.peek() {
+            None | Some(&Token::Or) => Err(EmptyRange),
+            _ => self.predicates().map(Some),
+        }
     }
 
     /// Parse a single component.
     ///
     /// Returns `None` if the component is a wildcard.
     pub fn component(&mut self) -> Result<Option<u64>, Error<'input>> {
+        let position = self.position;
+
         match self.pop()? {
             Token::Numeric(number) => Ok(Some(number)),
             ref t if t.is_wildcard() => Ok(None),
-            tok => Err(UnexpectedToken(tok)),
+            Token::AlphaNumeric(number) if has_leading_zero(number) => {
+                Err(LeadingZero(number, position))
+            }
+            tok => Err(UnexpectedToken(tok, position)),
         }
     }
 
     /// Parse a single numeric.
     pub fn numeric(&mut self) -> Result<u64, Error<'input>> {
+        let position = self.position;
+
         match self.pop()? {
             Token::Numeric(number) => Ok(number),
-            tok => Err(UnexpectedToken(tok)),
+            Token::AlphaNumeric(number) if has_leading_zero(number) => {
+                Err(LeadingZero(number, position))
+            }
+            tok => Err(UnexpectedToken(tok, position)),
         }
     }
 
@@ -217,27 +560,90 @@ impl<'input> Parser<'input> {
         self.component().map(|n| (n, n.is_none()))
     }
 
+    /// Parse a single numeric, saturating at `u64::MAX` if it does not fit.
+    ///
+    /// The second component of the tuple indicates if the numeric was saturated.
+    fn saturating_numeric(&mut self) -> Result<(u64, bool), Error<'input>> {
+        let position = self.position;
+
+        match self.pop()? {
+            Token::Numeric(number) => Ok((number, false)),
+            Token::AlphaNumeric(number) if has_leading_zero(number) => {
+                Err(LeadingZero(number, position))
+            }
+            // the lexer only falls back to alphanumeric for all digits if the number overflows.
+            Token::AlphaNumeric(number) if number.bytes().all(|b| b.is_ascii_digit()) => {
+                Ok((u64::MAX, true))
+            }
+            tok => Err(UnexpectedToken(tok, position)),
+        }
+    }
+
     /// Parse a dot, then a numeric.
     pub fn dot_numeric(&mut self) -> Result<u64, Error<'input>> {
+        let position = self.position;
+
         match self.pop()? {
             Token::Dot => {}
-            tok => return Err(UnexpectedToken(tok)),
+            tok => return Err(UnexpectedToken(tok, position)),
         }
 
         self.numeric()
     }
 
+    /// Parse a dot, then a numeric saturating at `u64::MAX`.
+    fn dot_saturating_numeric(&mut self) -> Result<(u64, bool), Error<'input>> {
+        let position = self.position;
+
+        match self.pop()? {
+            Token::Dot => {}
+            tok => return Err(UnexpectedToken(tok, position)),
+        }
+
+        self.saturating_numeric()
+    }
+
     /// Parse an string identifier.
     ///
     /// Like, `foo`, or `bar`.
     pub fn identifier(&mut self) -> Result<Identifier, Error<'input>> {
+        let position = self.position;
+
         let identifier = match self.pop()? {
             Token::AlphaNumeric(identifier) => {
                 // TODO: Borrow?
                 Identifier::AlphaNumeric(identifier.to_string())
             }
             Token::Numeric(n) => Identifier::Numeric(n),
-            tok => return Err(UnexpectedToken(tok)),
+            tok => return Err(UnexpectedToken(tok, position)),
+        };
+
+        Ok(identifier)
+    }
+
+    /// Parse an identifier without keeping it.
+    fn skip_identifier(&mut self) -> Result<(), Error<'input>> {
+        let position = self.position;
+
+        match self.pop()? {
+            Token::AlphaNumeric(_) | Token::Numeric(_) => Ok(()),
+            tok => Err(UnexpectedToken(tok, position)),
+        }
+    }
+
+    /// Parse an identifier, sharing alphanumeric ones through the given interner.
+    fn interned_identifier(
+        &mut self,
+        interner: &mut Interner,
+    ) -> Result<InternedIdentifier, Error<'input>> {
+        let position = self.position;
+
+        let identifier = match self.pop()? {
+            Token::AlphaNumeric(identifier) => {
+                InternedIdentifier::AlphaNumeric(interner.intern(identifier))
+            }
+            Token::Numeric(n) => InternedIdentifier::Numeric(n),
+            tok => return Err(UnexpectedToken(tok, position)),
         };
 
         Ok(identifier)
@@ -247,6 +653,16 @@ impl<'input> Parser<'input> {
     ///
     /// Like, `abcdef.1234`.
     fn pre(&mut self) -> Result<Vec<Identifier>, Error<'input>> {
+        self.pre_with(Parser::identifier)
+    }
+
+    /// Parse all pre-release identifiers with the given identifier parser.
+    ///
+    /// Unlike build metadata, prerelease identifiers must not be numeric with a leading zero.
+    fn pre_with<T, F>(&mut self, identifier: F) -> Result<Vec<T>, Error<'input>>
+    where
+        F: FnMut(&mut Parser<'input>) -> Result<T, Error<'input>>,
+    {
         match self.peek() {
             Some(&Token::Hyphen) => {}
             _ => return Ok(vec![]),
@@ -254,25 +670,70 @@ impl<'input> Parser<'input> {
 
         // pop the peeked hyphen.
         self.pop()?;
-        self.parts()
+        self.pre_parts(identifier)
+    }
+
+    /// Parse prerelease identifiers without the leading hyphen.
+    ///
+    /// Like, `alpha.1`.
+    pub fn pre_release(&mut self) -> Result<Vec<Identifier>, Error<'input>> {
+        self.pre_parts(Parser::identifier)
+    }
+
+    /// Parse build metadata identifiers without the leading plus.
+    ///
+    /// Like, `build.5`.
+    pub fn build_metadata(&mut self) -> Result<Vec<Identifier>, Error<'input>> {
+        self.parts(Parser::identifier)
+    }
+
+    /// Parse a dot-separated set of prerelease identifiers, rejecting numeric ones with a leading
+    /// zero.
+    fn pre_parts<T, F>(&mut self, mut identifier: F) -> Result<Vec<T>, Error<'input>>
+    where
+        F: FnMut(&mut Parser<'input>) -> Result<T, Error<'input>>,
+    {
+        self.parts(|parser| {
+            if let Some(&Token::AlphaNumeric(number)) = parser.peek() {
+                if has_leading_zero(number) {
+                    return Err(LeadingZero(number, parser.position));
+                }
+            }
+
+            identifier(parser)
+        })
     }
 
     /// Parse a dot-separated set of identifiers.
-    fn parts(&mut self) -> Result<Vec<Identifier>, Error<'input>> {
+    fn parts<T, F>(&mut self, mut identifier: F) -> Result<Vec<T>, Error<'input>>
+    where
+        F: FnMut(&mut Parser<'input>) -> Result<T, Error<'input>>,
+    {
         let mut parts = Vec::new();
 
-        parts.push(self.identifier()?);
-
         loop {
+            // catch empty identifiers, like in `1.0.0-alpha..1` or `1.0.0+`.
+            match self.peek() {
+                None | Some(&Token::Dot) | Some(&Token::Plus) | Some(&Token::Whitespace(..)) |
+                Some(&Token::Comma) | Some(&Token::Or) => {
+                    return Err(EmptyIdentifier(self.position));
+                }
+                _ => {}
+            }
+
+            if parts.len() == self.limits.max_identifiers {
+                return Err(LimitExceeded(Limit::Identifiers, self.position));
+            }
+
+            parts.push(identifier(self)?);
+
             match self.peek() {
                 Some(&Token::Dot) => {}
                 _ => break,
             }
 
-            // pop the peeked hyphen.
+            // pop the peeked dot.
             self.pop()?;
-
-            parts.push(self.identifier()?);
         }
 
         Ok(parts)
@@ -282,6 +743,14 @@ impl<'input> Parser<'input> {
     ///
     /// Like, `` (empty), or `+abcdef`.
     fn plus_build_metadata(&mut self) -> Result<Vec<Identifier>, Error<'input>> {
+        self.plus_build_metadata_with(Parser::identifier)
+    }
+
+    /// Parse optional build metadata with the given identifier parser.
+    fn plus_build_metadata_with<T, F>(&mut self, identifier: F) -> Result<Vec<T>, Error<'input>>
+    where
+        F: FnMut(&mut Parser<'input>) -> Result<T, Error<'input>>,
+    {
         match self.peek() {
             Some(&Token::Plus) => {}
             _ => return Ok(vec![]),
@@ -289,12 +758,16 @@ impl<'input> Parser<'input> {
 
         // pop the plus.
         self.pop()?;
-        self.parts()
+        self.parts(identifier)
     }
 
     /// Optionally parse a single operator.
     ///
     /// Like, `~`, or `^`.
+    ///
+    /// Any whitespace after the operator is skipped, so `>=1.0.0` and `>= 1.0.0` are the same.
+    /// Two-character operators are single tokens though, whitespace inside them like in `> =` is
+    /// rejected as two chained operators.
     pub fn op(&mut self) -> Result<Op, Error<'input>> {
         use self::Token::*;
 
@@ -305,63 +778,118 @@ impl<'input> Parser<'input> {
             Some(&Lt) => Op::Lt,
             Some(&LtEq) => Op::LtEq,
             Some(&Tilde) => Op::Tilde,
+            Some(&Pessimistic) => Op::Pessimistic,
             Some(&Caret) => Op::Compatible,
             // default op
             _ => return Ok(Op::Compatible),
         };
 
         // remove the matched token.
-        self.pop()?;
+        let first = self.pop()?;
         self.skip_whitespace()?;
+
+        // catch chained operators, like `>= >=` or `>==`.
+        if self.peek().map(Token::is_operator).unwrap_or(false) {
+            let position = self.position;
+            let second = self.pop()?;
+            return Err(UnexpectedOperator(first, second, position));
+        }
+
         Ok(op)
     }
 
     /// Parse a single predicate.
     ///
     /// Like, `^1`, or `>=2.0.0`.
+    ///
+    /// Without an operator or with `=`, a wildcard version like `1.*` is parsed into an
+    /// `Op::Wildcard`. Any other operator treats the wildcarded components as left out, so `>=1.*`
+    /// is the same as `>=1`. A bare wildcard matches anything and gives `None`, except for `<*`
+    /// and `>*` which are rejected since they could never match.
     pub fn predicate(&mut self) -> Result<Option<Predicate>, Error<'input>> {
         // empty predicate, treated the same as wildcard.
-        if self.peek().is_none() {
-            return Ok(None);
+        match self.peek() {
+            None | Some(&Token::Or) => return Ok(None),
+            // leading comma, like `, >1.0.0`.
+            Some(&Token::Comma) => return Err(EmptyPredicate(self.position)),
+            _ => {}
         }
 
+        let position = self.position;
+        let explicit_op = self.peek().map(Token::is_operator).unwrap_or(false);
         let mut op = self.op()?;
 
+        let major_position = self.position;
         let major = match self.component()? {
             Some(major) => major,
+            // `<*` and `>*` could never match anything.
+            None if op == Op::Lt || op == Op::Gt => {
+                return Err(WildcardAfterOperator(op, major_position));
+            }
+            None if self.explicit_major_wildcard => {
+                self.count_predicate(position)?;
+
+                return Ok(Some(Predicate {
+                    op: Op::Wildcard(WildcardVersion::Major),
+                    major: 0,
+                    minor: None,
+                    patch: None,
+                    pre: Vec::new(),
+                    build: Vec::new(),
+                    raw: self.input[position..self.position].to_string(),
+                }));
+            }
             None => return Ok(None),
         };
 
+        self.count_predicate(position)?;
+
         let (minor, minor_wildcard) = self.dot_component()?;
-        let (patch, patch_wildcard) = self.dot_component()?;
-        let pre = self.pre()?;
+        let (mut patch, patch_wildcard) = self.dot_component()?;
 
-        // TODO: avoid illegal combinations, like `1.*.0`.
-        if minor_wildcard {
-            op = Op::Wildcard(WildcardVersion::Minor);
+        if self.strict && (minor.is_none() || patch.is_none()) {
+            if let Some(&Token::Hyphen) | Some(&Token::Plus) = self.peek() {
+                let position = self.position;
+                return Err(UnexpectedToken(self.pop()?, position));
+            }
         }
 
-        if patch_wildcard {
+        let pre = self.pre()?;
+
+        if explicit_op && op != Op::Ex {
+            // an operator applies to the wildcarded components as if they were left out, so
+            // `>=1.*` is the same as `>=1`.
+            if minor_wildcard {
+                patch = None;
+            }
+        } else if minor_wildcard {
+            // TODO: avoid illegal combinations, like `1.*.0`.
+            // the highest wildcarded component decides the kind, so `1.*.*` is a minor wildcard.
+            op = Op::Wildcard(WildcardVersion::Minor);
+        } else if patch_wildcard {
             op = Op::Wildcard(WildcardVersion::Patch);
         }
 
-        // ignore build metadata
-        self.plus_build_metadata()?;
+        let build = self.plus_build_metadata()?;
+        let raw = self.input[position..self.position].to_string();
 
         Ok(Some(Predicate {
-            op: op,
-            major: major,
-            minor: minor,
-            patch: patch,
-            pre: pre,
+            op,
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+            raw,
         }))
     }
 
-    /// Parse a single range.
+    /// Parse a set of predicates separated by commas or whitespace.
     ///
     /// Like, `^1.0` or `>=3.0.0, <4.0.0`.
-    pub fn range(&mut self) -> Result<VersionReq, Error<'input>> {
+    fn predicates(&mut self) -> Result<Vec<Predicate>, Error<'input>> {
         let mut predicates = Vec::new();
+        self.skip_empty_predicates()?;
 
         if let Some(predicate) = self.predicate()? {
             predicates.push(predicate);
@@ -371,7 +899,160 @@ impl<'input> Parser<'input> {
             }
         }
 
-        Ok(VersionReq { predicates: predicates })
+        Ok(predicates)
+    }
+
+    /// Count a parsed predicate starting at the given position against the limits.
+    fn count_predicate(&mut self, position: usize) -> Result<(), Error<'input>> {
+        if self.predicates == self.limits.max_predicates {
+            return Err(LimitExceeded(Limit::Predicates, position));
+        }
+
+        self.predicates += 1;
+        Ok(())
+    }
+
+    /// Run the given parse, or restore the parser to where it was if the parse fails or finds
+    /// nothing.
+    fn attempt<T, F>(&mut self, parse: F) -> Option<T>
+    where
+        F: FnOnce(&mut Parser<'input>) -> Result<Option<T>, Error<'input>>,
+    {
+        let snapshot = self.clone();
+
+        match parse(self) {
+            Ok(Some(value)) => Some(value),
+            _ => {
+                *self = snapshot;
+                None
+            }
+        }
+    }
+
+    /// Parse as many predicates of a single group as possible.
+    fn predicates_prefix(&mut self) -> Result<Vec<Predicate>, Error<'input>> {
+        let mut predicates = Vec::new();
+
+        if let Some(predicate) = self.predicate()? {
+            predicates.push(predicate);
+
+            while let Some(next) = self.attempt(Parser::comma_predicate) {
+                predicates.push(next);
+            }
+        }
+
+        Ok(predicates)
+    }
+
+    /// Parse as many predicates as possible of a group after `||`.
+    fn or_predicates_prefix(&mut self) -> Result<Option<Vec<Predicate>>, Error<'input>> {
+        if !has_ws_separator!(self, Some(&Token::Or)) {
+            return Ok(None);
+        }
+
+        match self.peek() {
+            None | Some(&Token::Or) => Ok(None),
+            _ => self.predicates_prefix().map(Some),
+        }
+    }
+
+    /// Parse a range off the front of the input, stopping before the first part that does not
+    /// belong to it.
+    ///
+    /// Unlike [`range`], this does not fail on trailing input, which can be retrieved with
+    /// [`remaining`] afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use semver_parser::parser::Parser;
+    ///
+    /// let mut p = Parser::new(">=1.0.0, <2.0.0 and more").expect("a broken parser");
+    ///
+    /// assert_eq!(">=1.0.0, <2.0.0", p.range_prefix().unwrap().to_string());
+    /// assert_eq!(" and more", p.remaining());
+    /// ```
+    ///
+    /// [`range`]: #method.range
+    /// [`remaining`]: #method.remaining
+    pub fn range_prefix(&mut self) -> Result<VersionReq, Error<'input>> {
+        if let Some(&Token::Or) = self.peek() {
+            return Err(EmptyRange);
+        }
+
+        let predicates = self.predicates_prefix()?;
+        let mut or_groups = Vec::new();
+
+        while let Some(next) = self.attempt(Parser::or_predicates_prefix) {
+            or_groups.push(next);
+        }
+
+        Ok(VersionReq {
+            predicates,
+            or_groups,
+        })
+    }
+
+    /// Parse a single range.
+    ///
+    /// Like, `^1.0`, `>=3.0.0, <4.0.0` or `1.0 || >=3.0.0, <4.0.0`.
+    pub fn range(&mut self) -> Result<VersionReq, Error<'input>> {
+        self.skip_empty_predicates()?;
+
+        if let Some(&Token::Or) = self.peek() {
+            return Err(EmptyRange);
+        }
+
+        let predicates = self.predicates()?;
+        let mut or_groups = Vec::new();
+
+        while let Some(next) = self.or_predicates()? {
+            or_groups.push(next);
+        }
+
+        Ok(VersionReq {
+            predicates,
+            or_groups,
+        })
+    }
+
+    /// Parse a range without building it, only checking that it is valid.
+    ///
+    /// Like [`range`], but every predicate is dropped right after parsing it instead of being
+    /// collected into a [`VersionReq`].
+    ///
+    /// [`range`]: #method.range
+    /// [`VersionReq`]: ../range/struct.VersionReq.html
+    pub fn validate_range(&mut self) -> Result<(), Error<'input>> {
+        self.skip_empty_predicates()?;
+
+        if let Some(&Token::Or) = self.peek() {
+            return Err(EmptyRange);
+        }
+
+        self.validate_predicates()?;
+
+        while has_ws_separator!(self, Some(&Token::Or)) {
+            self.skip_empty_predicates()?;
+
+            match self.peek() {
+                None | Some(&Token::Or) => return Err(EmptyRange),
+                _ => self.validate_predicates()?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse a set of predicates separated by commas or whitespace, without keeping them.
+    fn validate_predicates(&mut self) -> Result<(), Error<'input>> {
+        self.skip_empty_predicates()?;
+
+        if self.predicate()?.is_some() {
+            while self.comma_predicate()?.is_some() {}
+        }
+
+        Ok(())
     }
 
     /// Parse a comparator.
@@ -379,13 +1060,19 @@ impl<'input> Parser<'input> {
     /// Like, `1.0 || 2.0` or `^1 || >=3.0.0, <4.0.0`.
     pub fn comparator(&mut self) -> Result<Comparator, Error<'input>> {
         let mut ranges = Vec::new();
-        ranges.push(self.range()?);
-
-        while let Some(next) = self.or_range()? {
-            ranges.push(next);
+        ranges.push(VersionReq {
+            predicates: self.predicates()?,
+            or_groups: Vec::new(),
+        });
+
+        while let Some(next) = self.or_predicates()? {
+            ranges.push(VersionReq {
+                predicates: next,
+                or_groups: Vec::new(),
+            });
         }
 
-        Ok(Comparator { ranges: ranges })
+        Ok(Comparator { ranges })
     }
 
     /// Parse a version.
@@ -403,14 +1090,160 @@ impl<'input> Parser<'input> {
         self.skip_whitespace()?;
 
         Ok(Version {
-            major: major,
-            minor: minor,
-            patch: patch,
-            pre: pre,
-            build: build,
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        })
+    }
+
+    /// Parse a version without building it, only checking that it is valid.
+    ///
+    /// Like [`version`], but identifiers are not allocated.
+    ///
+    /// [`version`]: #method.version
+    pub fn validate_version(&mut self) -> Result<(), Error<'input>> {
+        self.skip_whitespace()?;
+
+        self.numeric()?;
+        self.dot_numeric()?;
+        self.dot_numeric()?;
+        // a `Vec` of zero-sized items does not allocate.
+        self.pre_with(Parser::skip_identifier)?;
+        self.plus_build_metadata_with(Parser::skip_identifier)?;
+
+        self.skip_whitespace()
+    }
+
+    /// Parse a version, along with the byte ranges of its components in the input.
+    ///
+    /// Like, `1.2.3-alpha.1` where the prerelease spans `6..13`.
+    pub fn spanned_version(&mut self) -> Result<(Version, VersionSpans), Error<'input>> {
+        self.skip_whitespace()?;
+
+        let (major, major_span) = self.spanned(Parser::numeric)?;
+        let (minor, minor_span) = self.spanned(Parser::dot_numeric)?;
+        let (patch, patch_span) = self.spanned(Parser::dot_numeric)?;
+        let (pre, pre_span) = self.spanned(Parser::pre)?;
+        let (build, build_span) = self.spanned(Parser::plus_build_metadata)?;
+
+        self.skip_whitespace()?;
+
+        // the separators are single bytes, and not part of the spans.
+        let strip = |span: Range<usize>| span.start + 1..span.end;
+        let spans = VersionSpans {
+            major: major_span,
+            minor: strip(minor_span),
+            patch: strip(patch_span),
+            pre: if pre.is_empty() { None } else { Some(strip(pre_span)) },
+            build: if build.is_empty() { None } else { Some(strip(build_span)) },
+        };
+
+        let version = Version {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        };
+
+        Ok((version, spans))
+    }
+
+    /// Run the given parse, also returning the byte range of the input it consumed.
+    fn spanned<T, F>(&mut self, parse: F) -> Result<(T, Range<usize>), Error<'input>>
+    where
+        F: FnOnce(&mut Parser<'input>) -> Result<T, Error<'input>>,
+    {
+        let start = self.position;
+        let value = parse(self)?;
+
+        Ok((value, start..self.position))
+    }
+
+    /// Parse a version, sharing its alphanumeric identifiers through the given interner.
+    pub fn interned_version(
+        &mut self,
+        interner: &mut Interner,
+    ) -> Result<InternedVersion, Error<'input>> {
+        self.skip_whitespace()?;
+
+        let major = self.numeric()?;
+        let minor = self.dot_numeric()?;
+        let patch = self.dot_numeric()?;
+        let pre = self.pre_with(|parser| parser.interned_identifier(interner))?;
+        let build = self.plus_build_metadata_with(|parser| parser.interned_identifier(interner))?;
+
+        self.skip_whitespace()?;
+
+        Ok(InternedVersion {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
         })
     }
 
+    /// Parse a version, saturating components which do not fit in `u64` at `u64::MAX`.
+    ///
+    /// The second component of the tuple indicates if any component was saturated.
+    pub fn saturating_version(&mut self) -> Result<(Version, bool), Error<'input>> {
+        self.skip_whitespace()?;
+
+        let (major, major_overflow) = self.saturating_numeric()?;
+        let (minor, minor_overflow) = self.dot_saturating_numeric()?;
+        let (patch, patch_overflow) = self.dot_saturating_numeric()?;
+        let pre = self.pre()?;
+        let build = self.plus_build_metadata()?;
+
+        self.skip_whitespace()?;
+
+        let version = Version {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        };
+
+        Ok((version, major_overflow || minor_overflow || patch_overflow))
+    }
+
+    /// Parse a version where the minor and patch components may be missing.
+    ///
+    /// Missing components default to zero, so `1` is parsed as `1.0.0` and `1.2` as `1.2.0`.
+    pub fn partial_version(&mut self) -> Result<Version, Error<'input>> {
+        self.skip_whitespace()?;
+
+        let major = self.numeric()?;
+        let minor = self.optional_dot_numeric()?;
+        let patch = self.optional_dot_numeric()?;
+        let pre = self.pre()?;
+        let build = self.plus_build_metadata()?;
+
+        self.skip_whitespace()?;
+
+        Ok(Version {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        })
+    }
+
+    /// Optionally parse a dot, then a numeric.
+    ///
+    /// If a dot is not encountered, `0` is returned.
+    fn optional_dot_numeric(&mut self) -> Result<u64, Error<'input>> {
+        match self.peek() {
+            Some(&Token::Dot) => self.dot_numeric(),
+            _ => Ok(0),
+        }
+    }
+
     /// Check if we have reached the end of input.
     pub fn is_eof(&mut self) -> bool {
         self.c1.is_none()
@@ -418,7 +1251,9 @@ impl<'input> Parser<'input> {
 
     /// Get the rest of the tokens in the parser.
     ///
-    /// Useful for debugging.
+    /// Useful for debugging. Use [`position`] beforehand to find where they start.
+    ///
+    /// [`position`]: #method.position
     pub fn tail(&mut self) -> Result<Vec<Token<'input>>, Error<'input>> {
         let mut out = Vec::new();
 
@@ -426,10 +1261,379 @@ impl<'input> Parser<'input> {
             out.push(t);
         }
 
-        while let Some(t) = self.lexer.next() {
+        for t in &mut self.lexer {
             out.push(t?);
         }
 
         Ok(out)
     }
 }
+
+/// Check if an identifier is numeric but has a leading zero, like `01`.
+fn has_leading_zero(identifier: &str) -> bool {
+    identifier.len() > 1 && identifier.starts_with('0') &&
+        identifier.bytes().all(|b| b.is_ascii_digit())
+}
+
+// These also run without the `std` feature (`cargo test --no-default-features`), so tests
+// relying on `std::error::Error` are gated on it.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use range;
+    use version;
+
+    fn owned_error() -> ParseError {
+        let input = String::from("> 0.1.0,");
+        let error = ParseError::from(range::parse(&input).unwrap_err());
+        drop(input);
+        error
+    }
+
+    #[test]
+    pub fn owned_error_outlives_input() {
+        let error = owned_error();
+
+        assert_eq!("encountered empty predicate", error.message());
+        assert_eq!("encountered empty predicate", error.to_string());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    pub fn owned_error_boxed() {
+        use std::error::Error as StdError;
+
+        fn parse_boxed(input: &str) -> Result<range::VersionReq, Box<dyn StdError>> {
+            Ok(input.parse::<range::VersionReq>()?)
+        }
+
+        assert!(parse_boxed("^1.0").is_ok());
+        assert_eq!(
+            "encountered operator Eq after operator GtEq, offending input: `=`",
+            parse_boxed(">== 1.0").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    pub fn owned_error_text() {
+        let error = {
+            let input = String::from("1.2.3 a.b.c");
+            ParseError::from(version::parse(&input).unwrap_err())
+        };
+
+        assert_eq!(Some("a.b.c"), error.text());
+        assert_eq!(Some(6), error.position());
+        assert!(error.to_string().ends_with(", offending input: `a.b.c`"));
+
+        let texts = [
+            (">== 0.0.2", Some("=")),
+            ("> 0. 1", Some(" ")),
+            ("^01.0.0", Some("01")),
+            (">= 1.0 / 2.0", Some("/")),
+            ("1.0.0-alpha..1", None),
+        ];
+
+        for &(input, text) in texts.iter() {
+            assert_eq!(text, ParseError::from(range::parse(input).unwrap_err()).text());
+        }
+    }
+
+    #[test]
+    pub fn owned_error_into_string() {
+        let error: String = owned_error().into();
+
+        assert_eq!("encountered empty predicate", error);
+    }
+
+    #[test]
+    pub fn position() {
+        let mut p = Parser::new(">= 1.0").unwrap();
+
+        assert_eq!(0, p.position());
+        p.op().unwrap();
+        assert_eq!(3, p.position());
+        p.component().unwrap();
+        assert_eq!(4, p.position());
+        p.dot_component().unwrap();
+        assert_eq!(6, p.position());
+        assert!(p.is_eof());
+    }
+
+    #[test]
+    pub fn error_positions() {
+        assert_eq!(
+            Err(UnexpectedToken(Token::Whitespace(4, 5), 4)),
+            range::parse("> 0. 1")
+        );
+        assert_eq!(
+            Err(UnexpectedOperator(Token::GtEq, Token::Eq, 2)),
+            range::parse(">== 0.0.2")
+        );
+        assert_eq!(Some(2), range::parse(">== 0.0.2").unwrap_err().position());
+        assert_eq!(
+            Err(Lexer(lexer::Error::UnexpectedChar('/', 7))),
+            range::parse(">= 1.0 / 2.0")
+        );
+        assert_eq!(
+            Some(6),
+            version::parse("1.2.3 a.b.c").unwrap_err().position()
+        );
+        assert_eq!(Some(7), range::parse("> 0.1.0,").unwrap_err().position());
+    }
+
+    #[test]
+    pub fn owned_error_position() {
+        let error = ParseError::from(range::parse(">== 0.0.2").unwrap_err());
+
+        assert_eq!(Some(2), error.position());
+    }
+
+    #[test]
+    pub fn leading_zero() {
+        assert_eq!(Err(LeadingZero("01", 0)), version::parse("01.0.0"));
+        assert_eq!(Err(LeadingZero("02", 2)), version::parse("1.02.0"));
+        assert_eq!(Err(LeadingZero("01", 12)), version::parse("1.0.0-alpha.01"));
+        assert_eq!(Err(LeadingZero("00", 6)), version::parse("1.0.0-00"));
+        assert_eq!(Err(LeadingZero("01", 1)), range::parse(">01.0.0"));
+        assert_eq!(Err(LeadingZero("01", 5)), range::parse("^1.0.01"));
+    }
+
+    #[test]
+    pub fn leading_zero_allowed() {
+        assert!(version::parse("1.0.0-alpha.0").is_ok());
+        assert!(version::parse("1.0.0-0").is_ok());
+        assert!(version::parse("1.0.0-0abc").is_ok());
+        assert!(version::parse("1.0.0+001").is_ok());
+        assert!(range::parse(">=1.0.0-alpha.0").is_ok());
+    }
+
+    #[test]
+    pub fn leading_zero_message() {
+        assert_eq!(
+            "numeric identifier with leading zero: 01",
+            version::parse("01.0.0").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    pub fn chained_operators() {
+        assert_eq!(
+            Err(UnexpectedOperator(Token::GtEq, Token::GtEq, 3)),
+            range::parse(">= >= 0.0.2")
+        );
+        assert_eq!(
+            Err(UnexpectedOperator(Token::GtEq, Token::Eq, 2)),
+            range::parse(">== 0.0.2")
+        );
+        assert_eq!(
+            Err(UnexpectedOperator(Token::Caret, Token::Tilde, 10)),
+            range::parse(">=1.0.0, ^~1.2")
+        );
+        assert_eq!(
+            "encountered operator GtEq after operator GtEq",
+            range::parse(">= >= 0.0.2").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    pub fn whitespace_after_operator() {
+        assert_eq!(range::parse(">=1.0.0"), range::parse(">= 1.0.0"));
+        assert_eq!(range::parse(">=1.0.0"), range::parse(">=\t1.0.0"));
+        assert_eq!(range::parse("~>1.2"), range::parse("~> \t 1.2"));
+        assert_eq!(
+            Err(UnexpectedOperator(Token::Gt, Token::Eq, 2)),
+            range::parse("> = 1.0.0")
+        );
+        assert_eq!(
+            Err(UnexpectedOperator(Token::Lt, Token::Eq, 2)),
+            range::parse("<\t=1.0.0")
+        );
+        assert_eq!(
+            Err(UnexpectedOperator(Token::Tilde, Token::Gt, 2)),
+            range::parse("~ > 1.2")
+        );
+    }
+
+    #[test]
+    pub fn empty_identifier() {
+        assert_eq!(Err(EmptyIdentifier(12)), version::parse("1.0.0-alpha..1"));
+        assert_eq!(Err(EmptyIdentifier(6)), version::parse("1.0.0+"));
+        assert_eq!(Err(EmptyIdentifier(12)), version::parse("1.0.0+build..1"));
+        assert_eq!(Err(EmptyIdentifier(6)), version::parse("1.0.0-"));
+        assert_eq!(Err(EmptyIdentifier(6)), version::parse("1.0.0-+build"));
+        assert_eq!(Err(EmptyIdentifier(12)), version::parse("1.0.0-alpha."));
+        assert_eq!(Err(EmptyIdentifier(8)), range::parse(">=1.0.0-, <2.0.0"));
+        assert_eq!(
+            "encountered empty identifier",
+            version::parse("1.0.0+").unwrap_err().to_string()
+        );
+
+        assert!(version::parse("1.0.0-alpha.1+build.2").is_ok());
+    }
+
+    #[test]
+    pub fn limits_input_length() {
+        let input = format!("1.0.0{}", ",".repeat(1_000_000));
+
+        assert_eq!(
+            Err(LimitExceeded(Limit::InputLength, 1 << 16)),
+            range::parse(&input)
+        );
+        assert_eq!(
+            Err(LimitExceeded(Limit::InputLength, 1 << 16)),
+            version::parse(&input)
+        );
+
+        let limits = Limits {
+            max_input_length: usize::MAX,
+            ..Limits::default()
+        };
+        let mut p = Parser::with_limits(&input, limits).unwrap();
+        assert_eq!(Err(EmptyPredicate(5)), p.range());
+    }
+
+    #[test]
+    pub fn limits_predicates() {
+        let input = vec![">=1.0.0"; 1025].join(", ");
+        assert_eq!(
+            Err(LimitExceeded(Limit::Predicates, 1024 * 9)),
+            range::parse(&input)
+        );
+
+        let input = vec![">=1.0.0"; 1024].join(" || ");
+        assert!(range::parse(&input).is_ok());
+
+        let limits = Limits {
+            max_predicates: 2,
+            ..Limits::default()
+        };
+        let mut p = Parser::with_limits("1.0.0 || 2.0.0 || 3.0.0", limits).unwrap();
+        assert_eq!(Err(LimitExceeded(Limit::Predicates, 18)), p.range());
+    }
+
+    #[test]
+    pub fn limits_identifiers() {
+        let pre = vec!["a"; 1025].join(".");
+        let input = format!("1.0.0-{}", pre);
+        assert_eq!(
+            Err(LimitExceeded(Limit::Identifiers, 6 + 1024 * 2)),
+            version::parse(&input)
+        );
+
+        let limits = Limits {
+            max_identifiers: 2,
+            ..Limits::default()
+        };
+        let mut p = Parser::with_limits("1.0.0-alpha.1+build.1.2", limits).unwrap();
+        assert_eq!(Err(LimitExceeded(Limit::Identifiers, 22)), p.version());
+    }
+
+    #[test]
+    pub fn limits_message() {
+        assert_eq!(
+            "exceeded limit on number of predicates",
+            LimitExceeded(Limit::Predicates, 0).to_string()
+        );
+    }
+
+    #[test]
+    pub fn predicate_raw() {
+        let r = range::parse(">=  1.2.3").unwrap();
+        assert_eq!(">=  1.2.3", r.predicates[0].raw);
+
+        let r = range::parse("^1.2.3-alpha+build ,<2 || ~ 3.1  ").unwrap();
+        let raw: Vec<_> = r.groups().flat_map(|g| g.iter().map(|p| p.raw.as_str())).collect();
+        assert_eq!(vec!["^1.2.3-alpha+build", "<2", "~ 3.1"], raw);
+    }
+
+    #[test]
+    pub fn operator_with_wildcard() {
+        // `=` keeps the wildcard.
+        assert_eq!("1.*", range::parse("=1.*").unwrap().to_string());
+        assert_eq!("1.2.*", range::parse("=1.2.x").unwrap().to_string());
+
+        // other operators treat wildcarded components as left out.
+        assert_eq!(">=1", range::parse(">=1.*").unwrap().to_string());
+        assert_eq!(">=1", range::parse(">=1.*.*").unwrap().to_string());
+        assert_eq!(">=1.2", range::parse(">=1.2.*").unwrap().to_string());
+        assert_eq!("^1", range::parse("^1.x").unwrap().to_string());
+        assert_eq!("~1.2", range::parse("~1.2.X").unwrap().to_string());
+        assert_eq!("<1", range::parse("<1.*").unwrap().to_string());
+        assert_eq!(">1.2", range::parse(">1.2.*").unwrap().to_string());
+        assert_eq!("<=1", range::parse("<=1.*").unwrap().to_string());
+
+        // a bare wildcard matches anything, unless that would make it unsatisfiable.
+        for input in &["=*", ">=*", "<=*", "^*", "~*", "=x"] {
+            assert!(range::parse(input).unwrap().is_any(), "{}", input);
+        }
+
+        assert_eq!(Err(WildcardAfterOperator(Op::Lt, 1)), range::parse("<*"));
+        assert_eq!(Err(WildcardAfterOperator(Op::Gt, 2)), range::parse("> x"));
+        assert_eq!(
+            "operator < applied to a wildcard can never match",
+            range::parse("<*").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    pub fn operator_with_wildcard_matches() {
+        let v = |s| version::parse(s).unwrap();
+
+        let r = range::parse(">=1.*").unwrap();
+        assert!(r.matches(&v("1.0.0")));
+        assert!(r.matches(&v("2.5.0")));
+        assert!(!r.matches(&v("0.9.0")));
+
+        let r = range::parse("<1.2.*").unwrap();
+        assert!(r.matches(&v("1.1.9")));
+        assert!(!r.matches(&v("1.2.0")));
+    }
+
+    #[test]
+    pub fn range_prefix() {
+        let mut p = Parser::new("1.2.3 rest-of-input").unwrap();
+        assert_eq!(range::parse("1.2.3").unwrap(), p.range_prefix().unwrap());
+        assert_eq!(" rest-of-input", p.remaining());
+
+        let mut p = Parser::new(">=1.0, <2 || ~3.1 ; rest").unwrap();
+        assert_eq!(">=1.0, <2 || ~3.1", p.range_prefix().unwrap().to_string());
+        assert_eq!(" ; rest", p.remaining());
+
+        let mut p = Parser::new("^1.0 || foo").unwrap();
+        assert_eq!("^1.0", p.range_prefix().unwrap().to_string());
+        assert_eq!(" || foo", p.remaining());
+
+        let mut p = Parser::new("^1.0").unwrap();
+        assert_eq!("^1.0", p.range_prefix().unwrap().to_string());
+        assert_eq!("", p.remaining());
+        assert!(p.is_eof());
+
+        assert!(Parser::new("foo").unwrap().range_prefix().is_err());
+    }
+
+    #[test]
+    pub fn empty_predicate() {
+        assert_eq!(Err(EmptyPredicate(7)), range::parse("> 0.1.0,"));
+        assert_eq!(Err(EmptyPredicate(7)), range::parse("> 0.1.0, "));
+        assert_eq!(Err(EmptyPredicate(7)), range::parse("> 0.3.0, ,"));
+        assert_eq!(Err(EmptyPredicate(7)), range::parse("> 0.3.0,, <1.0.0"));
+        assert_eq!(Err(EmptyPredicate(0)), range::parse(", > 0.3.0"));
+        assert_eq!(Err(EmptyPredicate(11)), range::parse("1.0 || 2.0 , || 3.0"));
+        assert_eq!(Err(EmptyPredicate(5)), range::parse("1 || , 2"));
+    }
+
+    #[test]
+    pub fn error_clone_eq() {
+        let err = version::parse("1.2.3 x").unwrap_err();
+
+        assert_eq!(MoreInput(vec![Token::AlphaNumeric("x")], 6), err);
+        assert_eq!(err.clone(), err);
+        assert_ne!(MoreInput(vec![Token::AlphaNumeric("y")], 6), err);
+
+        assert_eq!(
+            Err(Lexer(lexer::Error::UnexpectedChar('!', 1))),
+            range::parse(">!1").map_err(|e| e.clone())
+        );
+        assert_eq!(Err(UnexpectedEnd), version::parse("1.2."));
+    }
+}
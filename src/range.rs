@@ -22,20 +22,15 @@
 //!         minor: Some(0),
 //!         patch: Some(0),
 //!         pre: Vec::new(),
+//!         build: Vec::new(),
+//!         raw: String::from("1.0.0"),
 //!     },
 //!     r.predicates[0]
 //! );
 //!
 //! let m = version::parse("1.0.0")?;
 //! for p in &r.predicates {
-//!     match p.op {
-//!         range::Op::Compatible => {
-//!             assert_eq!(p.major, m.major);
-//!         }
-//!         _ => {
-//!             unimplemented!();
-//!         }
-//!     }
+//!     assert!(p.matches(&m));
 //! }
 //! # Ok(())
 //! # }
@@ -48,9 +43,22 @@
 //! [`VersionReq`]: ./struct.VersionReq.html
 //! [`version::Version`]: ../version/struct.Version.html
 
-use parser::{self, Parser};
-use version::Identifier;
-use std::str::FromStr;
+use lexer;
+use parser::{self, ParseError, Parser};
+use version::{self, Identifier, Version};
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::str::{self, FromStr};
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter;
+use core::ops::{Bound, RangeBounds};
+use core::slice;
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 /// Struct holding collection of version requirements.
 ///
@@ -72,6 +80,8 @@ use std::str::FromStr;
 ///         minor: Some(0),
 ///         patch: Some(0),
 ///         pre: Vec::new(),
+///         build: Vec::new(),
+///         raw: String::from("1.0.0"),
 ///     },
 ///     r.predicates[0]
 /// );
@@ -97,6 +107,8 @@ use std::str::FromStr;
 ///         minor: Some(0),
 ///         patch: Some(9),
 ///         pre: Vec::new(),
+///         build: Vec::new(),
+///         raw: String::from("> 0.0.9"),
 ///     },
 ///     r.predicates[0]
 /// );
@@ -107,6 +119,8 @@ use std::str::FromStr;
 ///         minor: Some(5),
 ///         patch: Some(3),
 ///         pre: Vec::new(),
+///         build: Vec::new(),
+///         raw: String::from("<= 2.5.3"),
 ///     },
 ///     r.predicates[1]
 /// );
@@ -116,11 +130,1458 @@ use std::str::FromStr;
 /// # fn main() {
 /// #   try_main().unwrap();
 /// # }
+/// ```
 /// [`Predicate`]: ./struct.Predicate.html
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct VersionReq {
     /// Collection of predicates.
     pub predicates: Vec<Predicate>,
+    /// Alternative collections of predicates, separated by `||` in the input.
+    ///
+    /// The requirement matches if either `predicates` or any of these groups match. This is
+    /// empty for requirements without `||`.
+    pub or_groups: Vec<Vec<Predicate>>,
+}
+
+impl VersionReq {
+    /// Construct a requirement without predicates, which matches any release version.
+    ///
+    /// This is what `*` parses into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range::VersionReq;
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert!(VersionReq::any().matches(&version::parse("1.2.3")?));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn any() -> VersionReq {
+        VersionReq {
+            predicates: Vec::new(),
+            or_groups: Vec::new(),
+        }
+    }
+
+    /// Construct a requirement matching exactly the given [`version::Version`].
+    ///
+    /// The requirement has a single [`Op::Ex`] predicate with all numeric components and the
+    /// prerelease of the version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range::VersionReq;
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let v = version::parse("1.2.3-alpha")?;
+    /// let r = VersionReq::exact(&v);
+    ///
+    /// assert_eq!("=1.2.3-alpha", r.to_string());
+    /// assert!(r.matches(&v));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`version::Version`]: ../version/struct.Version.html
+    /// [`Op::Ex`]: ./enum.Op.html#variant.Ex
+    pub fn exact(version: &Version) -> VersionReq {
+        VersionReq {
+            predicates: vec![
+                Predicate {
+                    op: Op::Ex,
+                    major: version.major,
+                    minor: Some(version.minor),
+                    patch: Some(version.patch),
+                    pre: version.pre.clone(),
+                    build: Vec::new(),
+                    raw: String::new(),
+                },
+            ],
+            or_groups: Vec::new(),
+        }
+    }
+
+    /// Construct a requirement from predicates which must all match, checking that they are not
+    /// contradictory.
+    ///
+    /// Returns an error if no version could match all of the predicates, like for `=1.0.0` and
+    /// `=2.0.0`, or `>2.0.0` and `<1.0.0`. Only the tightest bounds of the predicates are
+    /// checked, so predicates which could only ever match prereleases are not rejected. An empty
+    /// list of predicates matches any release version, like [`any`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range::{self, VersionReq};
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let lower = range::parse_predicate(">=1.2.3")?.expect("non-empty");
+    /// let upper = range::parse_predicate("<2.0.0")?.expect("non-empty");
+    /// let r = VersionReq::from_predicates(vec![lower.clone(), upper])?;
+    /// assert_eq!(">=1.2.3, <2.0.0", r.to_string());
+    ///
+    /// let upper = range::parse_predicate("<1.0.0")?.expect("non-empty");
+    /// assert!(VersionReq::from_predicates(vec![lower, upper]).is_err());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`any`]: #method.any
+    pub fn from_predicates(predicates: Vec<Predicate>) -> Result<VersionReq, String> {
+        let normalized = predicates
+            .iter()
+            .flat_map(Predicate::to_comparators)
+            .collect::<Vec<_>>();
+
+        if group_is_empty(&normalized) {
+            return Err(String::from("Predicates can not match any version"));
+        }
+
+        Ok(VersionReq {
+            predicates,
+            or_groups: Vec::new(),
+        })
+    }
+
+    /// Check if the given [`version::Version`] matches every predicate of this requirement.
+    ///
+    /// If the requirement has several groups separated by `||`, it is enough for one of them to
+    /// match. A requirement without predicates (like `*`) matches any release. A prerelease version
+    /// only matches if at least one of the predicates explicitly opts into prereleases of the
+    /// same `major.minor.patch`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let r = range::parse(">= 1.2.0, < 2.0.0")?;
+    ///
+    /// assert!(r.matches(&version::parse("1.5.0")?));
+    /// assert!(!r.matches(&version::parse("2.0.0")?));
+    /// assert!(!r.matches(&version::parse("1.5.0-alpha")?));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`version::Version`]: ../version/struct.Version.html
+    pub fn matches(&self, version: &Version) -> bool {
+        self.groups().any(|predicates| group_matches(predicates, version))
+    }
+
+    /// Find the highest of the given versions which matches this requirement.
+    ///
+    /// Versions are compared by semver precedence using [`matches`], so prereleases are skipped
+    /// unless the requirement opts into them. Returns `None` if no version matches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let r = range::parse("^1.2")?;
+    /// let candidates = vec![
+    ///     version::parse("1.2.0")?,
+    ///     version::parse("1.4.1")?,
+    ///     version::parse("1.5.0-beta")?,
+    ///     version::parse("2.0.0")?,
+    /// ];
+    ///
+    /// assert_eq!(Some(&candidates[1]), r.satisfied_by_any(&candidates));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`matches`]: #method.matches
+    pub fn satisfied_by_any<'a>(&self, versions: &'a [Version]) -> Option<&'a Version> {
+        versions.iter().filter(|v| self.matches(v)).max()
+    }
+
+    /// Check if the given [`version::Version`] matches this requirement like [`matches`],
+    /// remembering the result in the given [`MatchCache`].
+    ///
+    /// Repeating a query for the same requirement and version is answered from the cache. Build
+    /// metadata is part of the key, so `1.2.0` and `1.2.0+build` are cached separately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range::{self, MatchCache};
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let r = range::parse("^1.2")?;
+    /// let v = version::parse("1.4.0")?;
+    /// let mut cache = MatchCache::new();
+    ///
+    /// assert!(r.matches_cached(&v, &mut cache));
+    /// assert!(r.matches_cached(&v, &mut cache));
+    /// assert_eq!(1, cache.hits());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`version::Version`]: ../version/struct.Version.html
+    /// [`matches`]: #method.matches
+    /// [`MatchCache`]: ./struct.MatchCache.html
+    pub fn matches_cached(&self, version: &Version, cache: &mut MatchCache) -> bool {
+        let reqs = cache.entries.entry(fingerprint(self)).or_default();
+        let index = match reqs.iter().position(|entry| entry.0 == *self) {
+            Some(index) => index,
+            None => {
+                reqs.push((self.clone(), BTreeMap::new()));
+                reqs.len() - 1
+            }
+        };
+
+        let results = reqs[index].1.entry(fingerprint(version)).or_default();
+
+        if let Some(&(_, matched)) = results.iter().find(|entry| entry.0 == *version) {
+            cache.hits += 1;
+            return matched;
+        }
+
+        let matched = self.matches(version);
+        results.push((version.clone(), matched));
+        matched
+    }
+
+    /// Check if the given [`version::Version`] matches this requirement, allowing prereleases.
+    ///
+    /// Unlike [`matches`], a prerelease version matches whenever it falls within the bounds of
+    /// every predicate of a group, like npm's `includePrerelease` option. A prerelease still sorts
+    /// before its release, so `>=1.0.0` allows `1.5.0-beta` but not `1.0.0-beta`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let r = range::parse(">=1.0.0")?;
+    /// let v = version::parse("1.5.0-beta")?;
+    ///
+    /// assert!(!r.matches(&v));
+    /// assert!(r.matches_prerelease(&v));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`version::Version`]: ../version/struct.Version.html
+    /// [`matches`]: #method.matches
+    pub fn matches_prerelease(&self, version: &Version) -> bool {
+        self.groups()
+            .any(|predicates| predicates.iter().all(|p| p.matches_prerelease(version)))
+    }
+
+    /// Check if this requirement matches any release version, like `*`.
+    ///
+    /// This is the case if it has a group without predicates, or with only
+    /// [`WildcardVersion::Major`] and `>=0.0.0` predicates, since cargo writes `*` as `>=0.0.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert!(range::parse("*")?.is_any());
+    /// assert!(range::parse(">=0.0.0")?.is_any());
+    /// assert!(!range::parse("1.*")?.is_any());
+    /// assert!(range::parse_explicit_wildcard("*")?.is_any());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`WildcardVersion::Major`]: ./enum.WildcardVersion.html#variant.Major
+    pub fn is_any(&self) -> bool {
+        self.groups().any(|predicates| {
+            predicates.iter().all(|p| match p.op {
+                Op::Wildcard(WildcardVersion::Major) => true,
+                Op::GtEq => {
+                    p.major == 0 && p.minor.unwrap_or(0) == 0 && p.patch.unwrap_or(0) == 0 &&
+                        p.pre.is_empty()
+                }
+                _ => false,
+            })
+        })
+    }
+
+    /// Check if this requirement is a single [`Op::Ex`] predicate, like `=1.2.3`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert!(range::parse("=1.2.3")?.is_exact());
+    /// assert!(!range::parse("1.2.3")?.is_exact());
+    /// assert!(!range::parse("=1.2.3 || =1.2.4")?.is_exact());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`Op::Ex`]: ./enum.Op.html#variant.Ex
+    pub fn is_exact(&self) -> bool {
+        self.or_groups.is_empty() && self.predicates.len() == 1 && self.predicates[0].op == Op::Ex
+    }
+
+    /// Check if any predicate of this requirement is an [`Op::Wildcard`], like `1.2.*`.
+    ///
+    /// A lone `*` has no predicates at all, see [`is_any`] for that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert!(range::parse("1.2.*")?.is_wildcard());
+    /// assert!(range::parse(">=1.0.0 || 2.x")?.is_wildcard());
+    /// assert!(!range::parse("*")?.is_wildcard());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`Op::Wildcard`]: ./enum.Op.html#variant.Wildcard
+    /// [`is_any`]: #method.is_any
+    pub fn is_wildcard(&self) -> bool {
+        self.groups().any(|predicates| {
+            predicates.iter().any(|p| matches!(p.op, Op::Wildcard(_)))
+        })
+    }
+
+    /// Check if any predicate of this requirement, in any group, names a prerelease like
+    /// `>=1.0.0-beta`.
+    ///
+    /// Prerelease versions can only match requirements for which this is `true`. [`matches`]
+    /// applies the rule more narrowly, per group and only to versions with the same
+    /// `major.minor.patch` as such a predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert!(range::parse(">=1.0.0-beta")?.contains_prerelease_predicate());
+    /// assert!(range::parse("^1 || =2.0.0-rc.1")?.contains_prerelease_predicate());
+    /// assert!(!range::parse(">=1.0.0")?.contains_prerelease_predicate());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`matches`]: #method.matches
+    pub fn contains_prerelease_predicate(&self) -> bool {
+        self.iter().any(Predicate::is_prerelease)
+    }
+
+    /// Iterate over the groups of predicates separated by `||`, starting with `predicates`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let r = range::parse("1.2 || >=2.0.0, <2.3.0")?;
+    ///
+    /// let sizes: Vec<_> = r.groups().map(|g| g.len()).collect();
+    /// assert_eq!(vec![1, 2], sizes);
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn groups(&self) -> impl Iterator<Item = &[Predicate]> {
+        iter::once(&self.predicates[..]).chain(self.or_groups.iter().map(|g| &g[..]))
+    }
+
+    /// The [`groups`] of this requirement in a canonical order, with the predicates of each group
+    /// sorted by operator and then version, and the groups sorted after that.
+    ///
+    /// This allows comparing requirements independently of the order they were written in, like
+    /// `>=1, <2 || ^5` and `^5 || <2, >=1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let a = range::parse(">=1, <2 || ^5")?;
+    /// let b = range::parse("^5 || <2, >=1")?;
+    ///
+    /// assert_ne!(a, b);
+    /// assert_eq!(a.predicates_sorted(), b.predicates_sorted());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`groups`]: #method.groups
+    pub fn predicates_sorted(&self) -> Vec<Vec<Predicate>> {
+        let mut groups: Vec<Vec<Predicate>> = self
+            .groups()
+            .map(|group| {
+                let mut group = group.to_vec();
+                group.sort();
+                group
+            })
+            .collect();
+
+        groups.sort();
+        groups
+    }
+
+    /// Iterate over all predicates of this requirement, including the ones of groups after `||`.
+    ///
+    /// Use [`groups`] to keep the groups apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let r = range::parse(">=1.2.0, <1.5.0 || ^2")?;
+    ///
+    /// assert_eq!(3, r.iter().count());
+    ///
+    /// for predicate in &r {
+    ///     assert!(predicate.major >= 1);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`groups`]: #method.groups
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            predicates: self.predicates.iter(),
+            or_groups: self.or_groups.iter(),
+        }
+    }
+
+    /// Expand every predicate into plain comparisons on complete versions.
+    ///
+    /// See [`Predicate::to_comparators`] for how each predicate is expanded. Groups separated by
+    /// `||` are kept apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let r = range::parse("^1.2.3, ~1.4")?;
+    ///
+    /// assert_eq!(">=1.2.3, <2.0.0, >=1.4.0, <1.5.0", r.normalize().to_string());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`Predicate::to_comparators`]: ./struct.Predicate.html#method.to_comparators
+    pub fn normalize(&self) -> VersionReq {
+        let normalize_group = |predicates: &[Predicate]| -> Vec<Predicate> {
+            predicates.iter().flat_map(Predicate::to_comparators).collect()
+        };
+
+        VersionReq {
+            predicates: normalize_group(&self.predicates),
+            or_groups: self.or_groups.iter().map(|g| normalize_group(g)).collect(),
+        }
+    }
+
+    /// Remove the predicates which are made redundant by stricter ones in the same group, like
+    /// `>=1.0.0` in `>=1.0.0, >=1.2.0`.
+    ///
+    /// A predicate is redundant if the tightest lower and upper bounds of its group stay the same
+    /// without it. The remaining predicates are kept as written, and predicates with a prerelease
+    /// are always kept since they decide which prereleases match. Groups separated by `||` are
+    /// simplified separately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert_eq!(">=1.2.0", range::parse(">=1.0.0, >=1.2.0")?.simplify().to_string());
+    /// assert_eq!("~1.4", range::parse("^1.2, ~1.4")?.simplify().to_string());
+    /// assert_eq!(">=1.0.0, <2.0.0", range::parse(">=1.0.0, <2.0.0")?.simplify().to_string());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn simplify(&self) -> VersionReq {
+        VersionReq {
+            predicates: simplify_group(&self.predicates),
+            or_groups: self.or_groups.iter().map(|g| simplify_group(g)).collect(),
+        }
+    }
+
+    /// Combine this requirement with another one, so that a version has to match both.
+    ///
+    /// The result is [normalized], and each of its groups is the concatenation of a group of this
+    /// requirement with a group of the other one. Returns `None` if no version can match both,
+    /// like for `<1.0.0` and `>=2.0.0`. Groups that provably cannot match anything are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let a = range::parse("^1.2")?;
+    /// let b = range::parse("<1.5.0")?;
+    ///
+    /// let both = a.intersect(&b).expect("overlapping");
+    /// assert_eq!(">=1.2.0, <2.0.0, <1.5.0", both.to_string());
+    ///
+    /// assert_eq!(None, a.intersect(&range::parse("2.0.0")?));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [normalized]: #method.normalize
+    pub fn intersect(&self, other: &VersionReq) -> Option<VersionReq> {
+        let (this, other) = (self.normalize(), other.normalize());
+
+        let mut groups = this.groups().flat_map(|a| {
+            other.groups().map(move |b| {
+                a.iter().chain(b.iter()).cloned().collect::<Vec<_>>()
+            })
+        }).filter(|group| !group_is_empty(group));
+
+        groups.next().map(|predicates| {
+            VersionReq {
+                predicates,
+                or_groups: groups.collect(),
+            }
+        })
+    }
+
+    /// Combine this requirement with another one, so that a version has to match either.
+    ///
+    /// The result is [normalized], with the groups of both requirements separated by `||`.
+    /// Groups which overlap or touch, like `<1.0.0` and `>=1.0.0`, are merged, unless they
+    /// involve prereleases, since merging them could change which prereleases match. Groups that
+    /// provably cannot match anything are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let a = range::parse("^1.2")?;
+    ///
+    /// assert_eq!(">=1.2.0, <3.0.0", a.union(&range::parse("^2")?).to_string());
+    /// assert_eq!(
+    ///     ">=1.2.0, <2.0.0 || >=3.0.0, <4.0.0",
+    ///     a.union(&range::parse("^3")?).to_string()
+    /// );
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [normalized]: #method.normalize
+    pub fn union(&self, other: &VersionReq) -> VersionReq {
+        let (this, other) = (self.normalize(), other.normalize());
+
+        merge_groups(this.groups().chain(other.groups()).collect())
+    }
+
+    /// Check if this requirement matches the same versions as another one, even if they are
+    /// written differently, like `1.2.*` and `>=1.2.0, <1.3.0`.
+    ///
+    /// Both requirements are [normalized], and their groups merged like with [`union`] before
+    /// comparing them. This is conservative: requirements that involve prereleases or cannot match
+    /// anything might compare unequal even if they match the same versions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let wildcard = range::parse("1.2.*")?;
+    ///
+    /// assert!(wildcard.semantically_eq(&range::parse(">=1.2.0, <1.3.0")?));
+    /// assert!(!wildcard.semantically_eq(&range::parse("^1.2.0")?));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [normalized]: #method.normalize
+    /// [`union`]: #method.union
+    pub fn semantically_eq(&self, other: &VersionReq) -> bool {
+        let canonical = |req: &VersionReq| {
+            let merged = merge_groups(req.normalize().groups().collect());
+
+            // groups with prereleases are not merged, so put them in a fixed order.
+            let mut groups = merged
+                .groups()
+                .map(|group| {
+                    let mut group = group.to_vec();
+                    group.sort();
+                    group.dedup();
+                    group
+                })
+                .collect::<Vec<_>>();
+            groups.sort();
+            groups.dedup();
+            groups
+        };
+
+        canonical(self) == canonical(other)
+    }
+
+    /// Check if every version matching this requirement also matches another one, like `^1.2.3`
+    /// and `^1`.
+    ///
+    /// Every group of this requirement has to fit in one group of the other requirement once
+    /// both are [normalized] and the groups of the other one are merged like with [`union`]. A
+    /// group allowing prereleases additionally needs the other group to allow prereleases of the
+    /// same versions. Requirements are subsets of themselves, and requirements which cannot match
+    /// anything are subsets of every requirement. This is conservative: some requirements which
+    /// only differ in the prereleases they could match might not be reported as subsets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let req = range::parse("^1.2.3")?;
+    ///
+    /// assert!(req.is_subset_of(&range::parse("^1")?));
+    /// assert!(req.is_subset_of(&range::parse(">=1.0.0")?));
+    /// assert!(!req.is_subset_of(&range::parse("~1.2")?));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [normalized]: #method.normalize
+    /// [`union`]: #method.union
+    pub fn is_subset_of(&self, other: &VersionReq) -> bool {
+        let this = self.normalize();
+        let other = merge_groups(other.normalize().groups().collect());
+
+        let prerelease_versions = |group: &[Predicate]| {
+            group
+                .iter()
+                .filter(|p| p.is_prerelease())
+                .map(|p| (p.major, p.minor, p.patch))
+                .collect::<Vec<_>>()
+        };
+
+        let is_subset = this
+            .groups()
+            .filter(|group| !group_is_empty(group))
+            .all(|group| {
+                let interval = group_interval(group);
+                let prereleases = prerelease_versions(group);
+
+                other.groups().any(|other_group| {
+                    let other_prereleases = prerelease_versions(other_group);
+
+                    interval_contains(group_interval(other_group), interval) &&
+                        prereleases.iter().all(|p| other_prereleases.contains(p))
+                })
+            });
+
+        is_subset
+    }
+
+    /// Format this requirement like `Display`, but writing wildcards with the character they were
+    /// parsed with, like `1.x` instead of `1.*`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let r = range::parse("1.x || 2.3.X")?;
+    ///
+    /// assert_eq!("1.* || 2.3.*", r.to_string());
+    /// assert_eq!("1.x || 2.3.X", r.to_faithful_string());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn to_faithful_string(&self) -> String {
+        Faithful::Req(self).to_string()
+    }
+
+    /// The lowest version matching this requirement, if any.
+    ///
+    /// For example `1.2.3` for `^1.2.3` and `1.2.0` for `~1.2`. Returns `None` for requirements
+    /// without a lower bound, like `<2.0.0` or `*`, and for requirements no version can match.
+    /// With several groups separated by `||`, the lowest of their minimal versions is returned,
+    /// unless one of the groups has no lower bound: `<1.0.0 || ^2` gives `None` as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    /// use semver_parser::version::Version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert_eq!(Some(Version::new(1, 2, 3)), range::parse("^1.2.3")?.minimal_version());
+    /// assert_eq!(Some(Version::new(1, 4, 0)), range::parse(">=1.2, ~1.4")?.minimal_version());
+    /// assert_eq!(None, range::parse("<2.0.0")?.minimal_version());
+    /// assert_eq!(None, range::parse("<1.0.0 || ^2")?.minimal_version());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn minimal_version(&self) -> Option<Version> {
+        let mut minimal: Option<Version> = None;
+
+        for group in self.groups() {
+            match group_minimal_version(group) {
+                Some(Bound::Unbounded) => return None,
+                Some(Bound::Included(version)) => {
+                    let lower = match minimal {
+                        Some(ref m) => version::compare(&version, m) == Ordering::Less,
+                        None => true,
+                    };
+
+                    if lower {
+                        minimal = Some(version);
+                    }
+                }
+                Some(Bound::Excluded(_)) | None => {}
+            }
+        }
+
+        minimal
+    }
+
+    /// The only version matching this requirement, if there is exactly one.
+    ///
+    /// This is the case for `=1.2.3`, but also for `^0.0.3` which allows `>=0.0.3, <0.0.4`. The
+    /// groups of the requirement are merged like with [`union`] first. This is conservative:
+    /// requirements with several groups involving prereleases, or with a range which only
+    /// prereleases could fall into, give `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    /// use semver_parser::version::Version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert_eq!(Some(Version::new(1, 2, 3)), range::parse("=1.2.3")?.pinned_version());
+    /// assert_eq!(Some(Version::new(0, 0, 3)), range::parse("^0.0.3")?.pinned_version());
+    /// assert_eq!(None, range::parse("^1.2.3")?.pinned_version());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`union`]: #method.union
+    pub fn pinned_version(&self) -> Option<Version> {
+        let merged = merge_groups(self.normalize().groups().collect());
+
+        if !merged.or_groups.is_empty() || group_is_empty(&merged.predicates) {
+            return None;
+        }
+
+        let group = &merged.predicates[..];
+
+        match group_interval(group) {
+            (Some((l, true)), Some((u, true))) if bound_cmp(l, u) == Ordering::Equal => {
+                return Some(Version {
+                    major: l.major,
+                    minor: l.minor.unwrap_or(0),
+                    patch: l.patch.unwrap_or(0),
+                    pre: l.pre.clone(),
+                    build: Vec::new(),
+                });
+            }
+            (_, None) => return None,
+            // prereleases between the bounds might match too.
+            _ if group.iter().any(Predicate::is_prerelease) => return None,
+            _ => {}
+        }
+
+        // the group only matches the releases between its bounds, so it is pinned if the release
+        // after the lowest one does not match.
+        let candidate = match group_minimal_version(group)? {
+            Bound::Included(candidate) => candidate,
+            _ => return None,
+        };
+
+        let next = successor(candidate.major, Some(candidate.minor), Some(candidate.patch))
+            .map(|(major, minor, patch)| Version::new(major, minor, patch));
+
+        match next {
+            Some(ref next) if group_matches(group, next) => None,
+            _ => Some(candidate),
+        }
+    }
+
+    /// The versions at the bounds of this requirement, useful as edge cases in tests.
+    ///
+    /// For every group of the [normalized] requirement this includes the lower bound and the
+    /// greatest release right below it, and the upper bound and the greatest release right below
+    /// it, whether the bounds are inclusive or not. The greatest release below a version can have
+    /// components at `u64::MAX`, like `1.18446744073709551615.18446744073709551615` below `2.0.0`.
+    /// The versions are sorted and without duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    /// use semver_parser::version::Version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert_eq!(
+    ///     vec![
+    ///         Version::new(1, 2, 2),
+    ///         Version::new(1, 2, 3),
+    ///         Version::new(1, 2, 9),
+    ///         Version::new(1, 2, 10),
+    ///     ],
+    ///     range::parse(">=1.2.3, <1.2.10")?.boundary_versions()
+    /// );
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [normalized]: #method.normalize
+    pub fn boundary_versions(&self) -> Vec<Version> {
+        let normalized = self.normalize();
+        let mut versions = Vec::new();
+
+        for group in normalized.groups() {
+            let (lower, upper) = group_interval(group);
+
+            for (bound, _) in lower.into_iter().chain(upper) {
+                let (major, minor, patch) =
+                    (bound.major, bound.minor.unwrap_or(0), bound.patch.unwrap_or(0));
+
+                if let Some((major, minor, patch)) = predecessor(major, minor, patch) {
+                    versions.push(Version::new(major, minor, patch));
+                }
+
+                versions.push(Version {
+                    major,
+                    minor,
+                    patch,
+                    pre: bound.pre.clone(),
+                    build: Vec::new(),
+                });
+            }
+        }
+
+        versions.sort_by(version::compare);
+        versions.dedup();
+        versions
+    }
+
+    /// The greatest version matching this requirement which is strictly below `ceiling`, if any.
+    ///
+    /// Only the bounds of the requirement are known, not which versions were published, so the
+    /// result is either an inclusive upper bound of the requirement or the greatest release right
+    /// below an exclusive one, which can have components at `u64::MAX`. For example, `^1.2.3`
+    /// below `1.5.0` gives `1.4.18446744073709551615`. Use [`satisfied_by_any`] to pick from a
+    /// list of known versions instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    /// use semver_parser::version::Version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let ceiling = Version::new(2, 0, 0);
+    ///
+    /// assert_eq!(
+    ///     Some(Version::new(1, 9, 0)),
+    ///     range::parse("<=1.9.0")?.max_version_below(&ceiling)
+    /// );
+    /// assert_eq!(None, range::parse("^2.1")?.max_version_below(&ceiling));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`satisfied_by_any`]: #method.satisfied_by_any
+    pub fn max_version_below(&self, ceiling: &Version) -> Option<Version> {
+        self.normalize()
+            .groups()
+            .filter_map(|group| group_max_version_below(group, ceiling))
+            .max_by(version::compare)
+    }
+}
+
+/// Combine groups of normalized predicates separated by `||`, merging the ones which overlap or
+/// touch and do not involve prereleases, and dropping the ones which cannot match anything.
+fn merge_groups(groups: Vec<&[Predicate]>) -> VersionReq {
+    let mut intervals = Vec::new();
+    let mut prerelease_groups = Vec::new();
+
+    for group in groups.iter().filter(|group| !group_is_empty(group)) {
+        if group.iter().any(Predicate::is_prerelease) {
+            prerelease_groups.push(group.to_vec());
+        } else {
+            intervals.push(group_interval(group));
+        }
+    }
+
+    intervals.sort_by(|a, b| match (a.0, b.0) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Less,
+        (Some(_), None) => Ordering::Greater,
+        (Some((a, a_inclusive)), Some((b, b_inclusive))) => {
+            bound_cmp(a, b).then(b_inclusive.cmp(&a_inclusive))
+        }
+    });
+
+    let mut merged = merge_intervals(intervals)
+        .into_iter()
+        .map(interval_group)
+        .chain(prerelease_groups)
+        .collect::<Vec<_>>();
+
+    // keep the requirement unsatisfiable if all groups were.
+    if merged.is_empty() {
+        merged = groups.iter().map(|group| group.to_vec()).collect();
+    }
+
+    // a group without predicates matches everything already.
+    if merged.iter().any(|group| group.is_empty()) {
+        return VersionReq {
+            predicates: Vec::new(),
+            or_groups: Vec::new(),
+        };
+    }
+
+    let predicates = merged.remove(0);
+
+    VersionReq {
+        predicates,
+        or_groups: merged,
+    }
+}
+
+/// The lowest version matching every predicate of a single group, as an included bound.
+///
+/// Gives `Bound::Unbounded` for a group without a lower bound which some version matches, and
+/// `None` if no version matches.
+fn group_minimal_version(predicates: &[Predicate]) -> Option<Bound<Version>> {
+    let candidate = predicates
+        .iter()
+        // an explicit `*` is no lower bound, like the empty group `*` parses to by default.
+        .filter(|p| p.op != Op::Wildcard(WildcardVersion::Major))
+        .flat_map(Predicate::to_comparators)
+        .filter_map(|c| {
+            let mut version = Version {
+                major: c.major,
+                minor: c.minor.unwrap_or(0),
+                patch: c.patch.unwrap_or(0),
+                pre: c.pre,
+                build: Vec::new(),
+            };
+
+            match c.op {
+                Op::Ex | Op::GtEq => {}
+                // the lowest version above a prerelease is the next prerelease, like
+                // `1.2.3-alpha.0` for `1.2.3-alpha`, and the next patch otherwise.
+                Op::Gt if version.is_prerelease() => version.pre.push(Identifier::Numeric(0)),
+                Op::Gt => version.patch = version.patch.checked_add(1)?,
+                _ => return None,
+            }
+
+            Some(version)
+        })
+        .max_by(version::compare);
+
+    match candidate {
+        Some(candidate) => {
+            if group_matches(predicates, &candidate) {
+                return Some(Bound::Included(candidate));
+            }
+
+            // a lower bound rounded up to a release can still let its first prerelease through,
+            // like `1.2.3-0` for `>1.2.2, <1.2.3-rc`.
+            if candidate.is_prerelease() {
+                return None;
+            }
+
+            let prerelease = Version {
+                pre: vec![Identifier::Numeric(0)],
+                ..candidate
+            };
+
+            if group_matches(predicates, &prerelease) {
+                Some(Bound::Included(prerelease))
+            } else {
+                None
+            }
+        }
+        None => {
+            // with only upper bounds, something matches if the lowest release or prerelease does.
+            let release = Version::new(0, 0, 0);
+            let prerelease = Version {
+                pre: vec![Identifier::Numeric(0)],
+                ..release.clone()
+            };
+
+            if group_matches(predicates, &release) || group_matches(predicates, &prerelease) {
+                Some(Bound::Unbounded)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// The greatest version strictly below `ceiling` matching every predicate of a single group of
+/// normalized predicates, if any.
+fn group_max_version_below(predicates: &[Predicate], ceiling: &Version) -> Option<Version> {
+    let upper = group_interval(predicates).1.map(|(u, inclusive)| {
+        let version = Version {
+            major: u.major,
+            minor: u.minor.unwrap_or(0),
+            patch: u.patch.unwrap_or(0),
+            pre: u.pre.clone(),
+            build: Vec::new(),
+        };
+
+        (version, inclusive)
+    });
+
+    // the inclusive upper bound itself, and the greatest release below the exclusive upper
+    // bound, or the ceiling if that is lower.
+    let mut candidates = Vec::new();
+    let mut exclusive = ceiling;
+
+    if let Some((ref version, inclusive)) = upper {
+        if inclusive {
+            candidates.push(version.clone());
+        }
+
+        if version::compare(version, ceiling) == Ordering::Less {
+            exclusive = version;
+        }
+    }
+
+    if let Some((major, minor, patch)) =
+        predecessor(exclusive.major, exclusive.minor, exclusive.patch)
+    {
+        candidates.push(Version::new(major, minor, patch));
+    }
+
+    candidates
+        .into_iter()
+        .filter(|v| version::compare(v, ceiling) == Ordering::Less)
+        .filter(|v| group_matches(predicates, v))
+        .max_by(version::compare)
+}
+
+/// Remove the predicates of a group which do not change its tightest bounds, keeping the ones with
+/// a prerelease.
+fn simplify_group(predicates: &[Predicate]) -> Vec<Predicate> {
+    let normalized = predicates
+        .iter()
+        .flat_map(Predicate::to_comparators)
+        .collect::<Vec<_>>();
+    let interval = group_interval(&normalized);
+
+    let mut kept = predicates.iter().collect::<Vec<_>>();
+    let mut i = 0;
+
+    while i < kept.len() {
+        if kept[i].is_prerelease() {
+            i += 1;
+            continue;
+        }
+
+        let others = kept
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .flat_map(|(_, p)| p.to_comparators())
+            .collect::<Vec<_>>();
+
+        if interval_eq(group_interval(&others), interval) {
+            kept.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+
+    kept.into_iter().cloned().collect()
+}
+
+/// Check if two intervals have the same bounds.
+fn interval_eq(a: Interval<'_>, b: Interval<'_>) -> bool {
+    let bound_eq = |a: Option<(&Predicate, bool)>, b: Option<(&Predicate, bool)>| match (a, b) {
+        (None, None) => true,
+        (Some((a, a_inclusive)), Some((b, b_inclusive))) => {
+            bound_cmp(a, b) == Ordering::Equal && a_inclusive == b_inclusive
+        }
+        _ => false,
+    };
+
+    bound_eq(a.0, b.0) && bound_eq(a.1, b.1)
+}
+
+/// Check if no version can match a group of normalized predicates.
+///
+/// Only looks at the tightest lower and upper bounds of the group, so this is conservative: a
+/// group for which this returns `false` might still not match anything because of the rules on
+/// prerelease versions.
+fn group_is_empty(predicates: &[Predicate]) -> bool {
+    match group_interval(predicates) {
+        (Some((l, l_inclusive)), Some((u, u_inclusive))) => match bound_cmp(l, u) {
+            Ordering::Greater => true,
+            Ordering::Equal => !(l_inclusive && u_inclusive),
+            Ordering::Less => false,
+        },
+        _ => false,
+    }
+}
+
+/// A lower and an upper bound, with whether the bound is inclusive. `None` means unbounded on
+/// that side.
+type Interval<'a> = (Option<(&'a Predicate, bool)>, Option<(&'a Predicate, bool)>);
+
+/// The tightest lower and upper bound of a group of normalized predicates.
+fn group_interval(predicates: &[Predicate]) -> Interval<'_> {
+    let mut lower: Option<(&Predicate, bool)> = None;
+    let mut upper: Option<(&Predicate, bool)> = None;
+
+    for p in predicates {
+        let (is_lower, is_upper) = (p.op.is_lower_bound(), p.op.is_upper_bound());
+        let inclusive = matches!(p.op, Op::Ex | Op::GtEq | Op::LtEq);
+
+        if is_lower {
+            lower = match lower {
+                Some((l, l_inclusive)) => match bound_cmp(p, l) {
+                    Ordering::Greater => Some((p, inclusive)),
+                    Ordering::Equal => Some((l, l_inclusive && inclusive)),
+                    Ordering::Less => Some((l, l_inclusive)),
+                },
+                None => Some((p, inclusive)),
+            };
+        }
+
+        if is_upper {
+            upper = match upper {
+                Some((u, u_inclusive)) => match bound_cmp(p, u) {
+                    Ordering::Less => Some((p, inclusive)),
+                    Ordering::Equal => Some((u, u_inclusive && inclusive)),
+                    Ordering::Greater => Some((u, u_inclusive)),
+                },
+                None => Some((p, inclusive)),
+            };
+        }
+    }
+
+    (lower, upper)
+}
+
+/// Merge intervals sorted by their lower bound which overlap or touch, like `<1.0.0` and
+/// `>=1.0.0`.
+fn merge_intervals(intervals: Vec<Interval<'_>>) -> Vec<Interval<'_>> {
+    let mut merged: Vec<Interval> = Vec::new();
+
+    for interval in intervals {
+        if let Some(last) = merged.last_mut() {
+            let touches = match (last.1, interval.0) {
+                (Some((u, u_inclusive)), Some((l, l_inclusive))) => match bound_cmp(u, l) {
+                    Ordering::Greater => true,
+                    Ordering::Equal => u_inclusive || l_inclusive,
+                    Ordering::Less => false,
+                },
+                _ => true,
+            };
+
+            if touches {
+                last.1 = match (last.1, interval.1) {
+                    (Some((a, a_inclusive)), Some((b, b_inclusive))) => match bound_cmp(a, b) {
+                        Ordering::Greater => Some((a, a_inclusive)),
+                        Ordering::Equal => Some((a, a_inclusive || b_inclusive)),
+                        Ordering::Less => Some((b, b_inclusive)),
+                    },
+                    _ => None,
+                };
+
+                continue;
+            }
+        }
+
+        merged.push(interval);
+    }
+
+    merged
+}
+
+/// Check if every version in the `inner` interval is also in the `outer` one.
+fn interval_contains(outer: Interval<'_>, inner: Interval<'_>) -> bool {
+    let lower = match (outer.0, inner.0) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some((o, o_inclusive)), Some((i, i_inclusive))) => match bound_cmp(o, i) {
+            Ordering::Less => true,
+            Ordering::Equal => o_inclusive || !i_inclusive,
+            Ordering::Greater => false,
+        },
+    };
+
+    let upper = match (outer.1, inner.1) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some((o, o_inclusive)), Some((i, i_inclusive))) => match bound_cmp(o, i) {
+            Ordering::Greater => true,
+            Ordering::Equal => o_inclusive || !i_inclusive,
+            Ordering::Less => false,
+        },
+    };
+
+    lower && upper
+}
+
+/// Turn an interval back into a group of normalized predicates.
+fn interval_group(interval: Interval<'_>) -> Vec<Predicate> {
+    let bound = |p: &Predicate, op| {
+        comparator(op, p.major, p.minor.unwrap_or(0), p.patch.unwrap_or(0), p.pre.clone())
+    };
+
+    match interval {
+        (Some((l, true)), Some((u, true))) if bound_cmp(l, u) == Ordering::Equal => {
+            vec![bound(l, Op::Ex)]
+        }
+        (lower, upper) => {
+            let lower = lower.map(|(l, inclusive)| {
+                bound(l, if inclusive { Op::GtEq } else { Op::Gt })
+            });
+            let upper = upper.map(|(u, inclusive)| {
+                bound(u, if inclusive { Op::LtEq } else { Op::Lt })
+            });
+
+            lower.into_iter().chain(upper).collect()
+        }
+    }
+}
+
+/// Compare the versions named by two normalized predicates, following version precedence.
+fn bound_cmp(a: &Predicate, b: &Predicate) -> Ordering {
+    (a.major, a.minor, a.patch)
+        .cmp(&(b.major, b.minor, b.patch))
+        .then_with(|| match (a.pre.is_empty(), b.pre.is_empty()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => a.pre.cmp(&b.pre),
+        })
+}
+
+/// Borrowing iterator over all predicates of a [`VersionReq`], created by [`VersionReq::iter`].
+///
+/// [`VersionReq`]: ./struct.VersionReq.html
+/// [`VersionReq::iter`]: ./struct.VersionReq.html#method.iter
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+    predicates: slice::Iter<'a, Predicate>,
+    or_groups: slice::Iter<'a, Vec<Predicate>>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a Predicate;
+
+    fn next(&mut self) -> Option<&'a Predicate> {
+        loop {
+            if let Some(predicate) = self.predicates.next() {
+                return Some(predicate);
+            }
+
+            self.predicates = self.or_groups.next()?.iter();
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a VersionReq {
+    type Item = &'a Predicate;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// Results of [`VersionReq::matches_cached`], for resolvers testing the same requirements against
+/// the same candidates over and over.
+///
+/// Requirements and versions are looked up by a 64-bit fingerprint of their hash, and then
+/// compared with `==` to rule out collisions, so each distinct requirement is stored once.
+///
+/// [`VersionReq::matches_cached`]: ./struct.VersionReq.html#method.matches_cached
+#[derive(Clone, Debug, Default)]
+pub struct MatchCache {
+    entries: BTreeMap<u64, Vec<(VersionReq, CachedResults)>>,
+    hits: usize,
+}
+
+/// The results for a single requirement, by the fingerprint of the version.
+type CachedResults = BTreeMap<u64, Vec<(Version, bool)>>;
+
+impl MatchCache {
+    /// Construct an empty cache.
+    pub fn new() -> MatchCache {
+        MatchCache::default()
+    }
+
+    /// The number of queries answered from this cache so far.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// The number of cached results.
+    pub fn len(&self) -> usize {
+        self.entries
+            .values()
+            .flat_map(|reqs| reqs.iter())
+            .flat_map(|entry| entry.1.values())
+            .map(Vec::len)
+            .sum()
+    }
+
+    /// Check if this cache holds no results.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Forget all cached results, and reset the number of hits.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.hits = 0;
+    }
+}
+
+/// The FNV-1a hash of a value, used as the key of a [`MatchCache`].
+///
+/// This is deterministic, unlike the hashers of `std`, and is available without `std`.
+///
+/// [`MatchCache`]: ./struct.MatchCache.html
+fn fingerprint<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = Fnv(0xcbf2_9ce4_8422_2325);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x100_0000_01b3);
+        }
+    }
+}
+
+/// Check if the given version matches every predicate of a single group.
+fn group_matches(predicates: &[Predicate], version: &Version) -> bool {
+    if predicates.is_empty() {
+        return !version.is_prerelease();
+    }
+
+    predicates.iter().all(|p| p.matches_numeric(version)) &&
+        predicates.iter().any(|p| p.pre_tag_is_compatible(version))
 }
 
 /// Enum representing a `*` version part.
@@ -148,14 +1609,53 @@ pub struct VersionReq {
 /// ```
 /// [`Op`]: ./enum.Op.html
 /// [`Predicate`]: ./struct.Predicate.html
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(PartialOrd, Ord, PartialEq, Eq, Debug, Hash, Clone)]
 pub enum WildcardVersion {
+    /// Wildcard major version `*`.
+    ///
+    /// This is only produced by [`parse_explicit_wildcard`], [`parse`] leaves a bare `*` out of
+    /// the predicates. The `major` of such a predicate is always `0`.
+    ///
+    /// [`parse_explicit_wildcard`]: ./fn.parse_explicit_wildcard.html
+    /// [`parse`]: ./fn.parse.html
+    Major,
     /// Wildcard minor version `1.*.3`.
+    ///
+    /// This is also used for `1.*.*`, since the minor version is the highest wildcarded
+    /// component.
     Minor,
     /// Wildcard patch version `1.2.*`.
     Patch,
 }
 
+/// The character a wildcard was written with, as given by [`Predicate::wildcard`].
+///
+/// [`Predicate::wildcard`]: ./struct.Predicate.html#method.wildcard
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WildcardShape {
+    /// `*`, like in `1.*`.
+    Star,
+    /// `x`, like in `1.x`.
+    LowerX,
+    /// `X`, like in `1.X`.
+    UpperX,
+}
+
+impl WildcardShape {
+    /// The character of this wildcard, like `"x"` for [`WildcardShape::LowerX`].
+    ///
+    /// [`WildcardShape::LowerX`]: #variant.LowerX
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            WildcardShape::Star => "*",
+            WildcardShape::LowerX => "x",
+            WildcardShape::UpperX => "X",
+        }
+    }
+}
+
 /// Enum representing operation in [`Predicate`].
 ///
 /// This enum represents an operation for comparing two [`version::Version`]s.
@@ -182,9 +1682,13 @@ pub enum WildcardVersion {
 /// ```
 /// [`Predicate`]: ./struct.Predicate.html
 /// [`version::Version`]: ../version/struct.Version.html
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(PartialOrd, Ord, PartialEq, Eq, Debug, Clone, Hash)]
 pub enum Op {
     /// Exact, `=`.
+    ///
+    /// Missing components are open, so `=1.2` allows any `1.2.x` release and `=1` any `1.x.y`,
+    /// rather than only `1.2.0` and `1.0.0`.
     Ex,
     /// Greater than, `>`.
     Gt,
@@ -199,31 +1703,167 @@ pub enum Op {
     Tilde,
     /// [Compatible](http://doc.crates.io/specifying-dependencies.html#caret-requirements)
     /// by definition of semver, indicated by `^`.
+    ///
+    /// The leftmost non-zero component may not change, so `^1.2.3` allows `<2.0.0`, `^0.2.3`
+    /// allows `<0.3.0` and `^0.0.3` only allows `0.0.3`, just like `^0.0.0` only allows `0.0.0`.
+    /// Missing components are open, so `^0` allows `<1.0.0` and `^0.0` allows `<0.1.0`.
     Compatible,
     /// `x.y.*`, `x.*`, `*`.
     Wildcard(WildcardVersion),
+    /// Pessimistic, `~>`, as used by Ruby's Bundler.
+    ///
+    /// Only the last given component may increase, so `~> 2.2` allows `>=2.2.0, <3.0.0` and
+    /// `~> 2.2.0` allows `>=2.2.0, <2.3.0`. Unlike with [`Op::Tilde`], where `~2.2` allows
+    /// `<2.3.0`, leaving out the patch version widens the range to the next major version. `~> 2`
+    /// allows `>=2.0.0, <3.0.0`.
+    ///
+    /// [`Op::Tilde`]: #variant.Tilde
+    Pessimistic,
 }
 
-impl FromStr for Op {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Op, String> {
-        match s {
-            "=" => Ok(Op::Ex),
-            ">" => Ok(Op::Gt),
-            ">=" => Ok(Op::GtEq),
-            "<" => Ok(Op::Lt),
-            "<=" => Ok(Op::LtEq),
-            "~" => Ok(Op::Tilde),
-            "^" => Ok(Op::Compatible),
-            _ => Err(String::from("Could not parse Op")),
-        }
-    }
-}
-
-/// Struct representing a version comparison predicate.
+/// The caret requirement cargo infers for a bare version, like `^1.2.3` for `1.2.3`.
 ///
-/// Struct contaions operation code and data for comparison of [`version::Version`]s.
+/// The prerelease is kept, the build metadata is left out since it never affects matching.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::range::VersionReq;
+/// use semver_parser::version;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let version = version::parse("1.2.3-alpha.1+build")?;
+///
+/// assert_eq!("^1.2.3-alpha.1", VersionReq::from(&version).to_string());
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+impl<'a> From<&'a Version> for VersionReq {
+    fn from(version: &'a Version) -> VersionReq {
+        VersionReq {
+            predicates: vec![comparator(
+                Op::Compatible,
+                version.major,
+                version.minor,
+                version.patch,
+                version.pre.clone(),
+            )],
+            or_groups: Vec::new(),
+        }
+    }
+}
+
+impl From<Version> for VersionReq {
+    fn from(version: Version) -> VersionReq {
+        VersionReq::from(&version)
+    }
+}
+
+impl Op {
+    /// Check if this operator puts a lower bound on the versions it allows.
+    ///
+    /// This is the case for all operators except `<` and `<=`. Wildcards like `1.*` bound the
+    /// allowed versions from both sides.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range::Op;
+    ///
+    /// assert!(Op::GtEq.is_lower_bound());
+    /// assert!(Op::Compatible.is_lower_bound());
+    /// assert!(!Op::Lt.is_lower_bound());
+    /// ```
+    pub fn is_lower_bound(&self) -> bool {
+        match *self {
+            Op::Ex | Op::Gt | Op::GtEq | Op::Tilde | Op::Compatible | Op::Wildcard(_) |
+            Op::Pessimistic => true,
+            Op::Lt | Op::LtEq => false,
+        }
+    }
+
+    /// Check if this operator puts an upper bound on the versions it allows.
+    ///
+    /// This is the case for all operators except `>` and `>=`. Wildcards like `1.*` bound the
+    /// allowed versions from both sides.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range::Op;
+    ///
+    /// assert!(Op::LtEq.is_upper_bound());
+    /// assert!(Op::Tilde.is_upper_bound());
+    /// assert!(!Op::Gt.is_upper_bound());
+    /// ```
+    pub fn is_upper_bound(&self) -> bool {
+        match *self {
+            Op::Ex | Op::Lt | Op::LtEq | Op::Tilde | Op::Compatible | Op::Wildcard(_) |
+            Op::Pessimistic => true,
+            Op::Gt | Op::GtEq => false,
+        }
+    }
+
+    /// The operator token for this `Op`, like `">="` for [`Op::GtEq`].
+    ///
+    /// This is the inverse of parsing an `Op` from a string. Wildcards are not written as an
+    /// operator but as part of the version, so `Op::Wildcard` is rendered as `"*"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range::{Op, WildcardVersion};
+    ///
+    /// assert_eq!("^", Op::Compatible.as_str());
+    /// assert_eq!("*", Op::Wildcard(WildcardVersion::Patch).as_str());
+    /// ```
+    /// [`Op::GtEq`]: ./enum.Op.html#variant.GtEq
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Op::Ex => "=",
+            Op::Gt => ">",
+            Op::GtEq => ">=",
+            Op::Lt => "<",
+            Op::LtEq => "<=",
+            Op::Tilde => "~",
+            Op::Compatible => "^",
+            Op::Wildcard(_) => "*",
+            Op::Pessimistic => "~>",
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Op {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Op, String> {
+        match s {
+            "=" => Ok(Op::Ex),
+            ">" => Ok(Op::Gt),
+            ">=" => Ok(Op::GtEq),
+            "<" => Ok(Op::Lt),
+            "<=" => Ok(Op::LtEq),
+            "~" => Ok(Op::Tilde),
+            "^" => Ok(Op::Compatible),
+            "~>" => Ok(Op::Pessimistic),
+            _ => Err(String::from("Could not parse Op")),
+        }
+    }
+}
+
+/// Struct representing a version comparison predicate.
+///
+/// Struct contaions operation code and data for comparison of [`version::Version`]s.
 ///
 /// # Examples
 ///
@@ -246,9 +1886,19 @@ impl FromStr for Op {
 /// #   try_main().unwrap();
 /// # }
 /// ```
+///
+/// Unlike build metadata on a [`version::Version`], the `build` field does not take part in
+/// equality, hashing or ordering of predicates.
+///
+/// Since 0.9.0 predicates also have the `build` and `raw` fields, which struct literals written
+/// for 0.8 have to add. [`Predicate::builder`] fills them in and keeps working if more fields are
+/// added.
+///
 /// [`Predicate`]: ./struct.Predicate.html
+/// [`Predicate::builder`]: #method.builder
 /// [`version::Version`]: ../version/struct.Version.html
-#[derive(PartialEq, Eq, Debug, Clone, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
 pub struct Predicate {
     /// Operation code for this predicate, like "greater than" or "exact match".
     pub op: Op,
@@ -260,75 +1910,738 @@ pub struct Predicate {
     pub patch: Option<u64>,
     /// Collection of `Identifier`s of version, like `"alpha1"` in `"1.2.3-alpha1"`.
     pub pre: Vec<Identifier>,
+    /// Collection of `Identifier`s of build metadata, like `"meta"` in `"=1.2.3+meta"`.
+    ///
+    /// Build metadata never affects matching, it is only retained so that a requirement can be
+    /// written back out unchanged.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub build: Vec<Identifier>,
+    /// The exact slice of the input this predicate was parsed from, like `">=  1.2.3"`, or empty
+    /// if it was not parsed.
+    ///
+    /// Like `build`, this never affects matching or comparisons, it allows tooling to reproduce
+    /// the original formatting.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub raw: String,
 }
 
-/// Function parsing [`Predicate`] from string.
-///
-/// Function parsing [`Predicate`] from string to `Result<`[`Predicate`]`, String>`,
-/// where `Err` will contain error message in case of failed parsing.
-///
-/// # Examples
-///
-/// Parsing [`Predicate`] from string and cheking its fields:
-///
-/// ```
-/// use semver_parser::range;
+impl PartialEq for Predicate {
+    fn eq(&self, other: &Predicate) -> bool {
+        self.op == other.op && self.major == other.major && self.minor == other.minor &&
+            self.patch == other.patch && self.pre == other.pre
+    }
+}
+
+impl Eq for Predicate {}
+
+impl Hash for Predicate {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.op.hash(state);
+        self.major.hash(state);
+        self.minor.hash(state);
+        self.patch.hash(state);
+        self.pre.hash(state);
+    }
+}
+
+impl PartialOrd for Predicate {
+    fn partial_cmp(&self, other: &Predicate) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Predicate {
+    fn cmp(&self, other: &Predicate) -> Ordering {
+        self.op
+            .cmp(&other.op)
+            .then_with(|| self.major.cmp(&other.major))
+            .then_with(|| self.minor.cmp(&other.minor))
+            .then_with(|| self.patch.cmp(&other.patch))
+            .then_with(|| self.pre.cmp(&other.pre))
+    }
+}
+
+impl Predicate {
+    /// Start building a predicate with the given operator and major version.
+    ///
+    /// See [`PredicateBuilder`] for the other components.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range::{self, Op, Predicate};
+    /// use semver_parser::version::Identifier;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let p = Predicate::builder(Op::GtEq, 1)
+    ///     .minor(2)
+    ///     .patch(3)
+    ///     .pre(vec![Identifier::AlphaNumeric(String::from("alpha"))])
+    ///     .build();
+    ///
+    /// assert_eq!(range::parse_predicate(">=1.2.3-alpha")?, Some(p));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`PredicateBuilder`]: ./struct.PredicateBuilder.html
+    pub fn builder(op: Op, major: u64) -> PredicateBuilder {
+        PredicateBuilder {
+            predicate: Predicate {
+                op,
+                major,
+                minor: None,
+                patch: None,
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+        }
+    }
+
+    /// Check if this predicate names a prerelease, like `>=1.2.3-alpha1`.
+    ///
+    /// Only such predicates allow prerelease versions to match.
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
+    /// Check if the given [`version::Version`] matches this predicate.
+    ///
+    /// Missing minor and patch components are treated as open, so `~1` matches any `1.x.y`
+    /// release. Following cargo, a prerelease version only matches if the predicate names the
+    /// same `major.minor.patch` and carries a prerelease itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let p = range::parse_predicate("~1.2")?.expect("non-empty");
+    ///
+    /// assert!(p.matches(&version::parse("1.2.7")?));
+    /// assert!(!p.matches(&version::parse("1.3.0")?));
+    /// assert!(!p.matches(&version::parse("1.2.7-alpha")?));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`version::Version`]: ../version/struct.Version.html
+    pub fn matches(&self, version: &Version) -> bool {
+        self.matches_numeric(version) && self.pre_tag_is_compatible(version)
+    }
+
+    /// Check if the given [`version::Version`] lies within the [`bounds`] of this predicate.
+    ///
+    /// Unlike [`matches`], prerelease versions are allowed without the predicate naming a
+    /// prerelease itself, so `~1.2` matches `1.2.7-alpha` but not `1.2.0-alpha`.
+    ///
+    /// [`version::Version`]: ../version/struct.Version.html
+    /// [`bounds`]: #method.bounds
+    /// [`matches`]: #method.matches
+    pub fn matches_prerelease(&self, version: &Version) -> bool {
+        self.compare(version) == PredicateMatch::Matches
+    }
+
+    /// Expand this predicate into plain comparisons on complete versions.
+    ///
+    /// The result only uses [`Op::Ex`], [`Op::Gt`], [`Op::GtEq`], [`Op::Lt`] and [`Op::LtEq`],
+    /// always with all of `major`, `minor` and `patch` set. For example `^1.2.3` becomes
+    /// `>=1.2.3, <2.0.0`, `~1.2` becomes `>=1.2.0, <1.3.0` and `1.2.*` becomes
+    /// `>=1.2.0, <1.3.0`. Carets follow the usual rules for a zero major version, so `^0.2.3`
+    /// becomes `>=0.2.3, <0.3.0` and `^0.0.3` becomes `>=0.0.3, <0.0.4`.
+    ///
+    /// Build metadata is dropped, since it never affects matching.
+    ///
+    /// An upper bound past the greatest possible version is left out, so `^18446744073709551615`
+    /// only becomes `>=18446744073709551615.0.0` and `<=18446744073709551615` has no comparators
+    /// at all. A lower bound past it, like for `>18446744073709551615`, becomes
+    /// `>18446744073709551615.18446744073709551615.18446744073709551615`, which matches nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let p = range::parse_predicate("^0.2.3")?.expect("non-empty");
+    /// let comparators: Vec<_> = p.to_comparators().iter().map(ToString::to_string).collect();
+    ///
+    /// assert_eq!(vec![">=0.2.3", "<0.3.0"], comparators);
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`Op::Ex`]: ./enum.Op.html#variant.Ex
+    /// [`Op::Gt`]: ./enum.Op.html#variant.Gt
+    /// [`Op::GtEq`]: ./enum.Op.html#variant.GtEq
+    /// [`Op::Lt`]: ./enum.Op.html#variant.Lt
+    /// [`Op::LtEq`]: ./enum.Op.html#variant.LtEq
+    pub fn to_comparators(&self) -> Vec<Predicate> {
+        let (major, minor, patch) = (self.major, self.minor, self.patch);
+        let lower = |op| {
+            comparator(op, major, minor.unwrap_or(0), patch.unwrap_or(0), self.pre.clone())
+        };
+        let upper = |bound: Option<(u64, u64, u64)>| {
+            bound.map(|(major, minor, patch)| comparator(Op::Lt, major, minor, patch, Vec::new()))
+        };
+
+        let (lower, upper) = match self.op {
+            Op::Ex if patch.is_some() => (Some(lower(Op::Ex)), None),
+            Op::Gt if patch.is_some() => (Some(lower(Op::Gt)), None),
+            Op::Lt | Op::LtEq if patch.is_some() => (None, Some(lower(self.op.clone()))),
+            Op::Ex => (Some(lower(Op::GtEq)), upper(successor(major, minor, None))),
+            Op::Gt => {
+                let lower = match successor(major, minor, None) {
+                    Some((major, minor, patch)) => {
+                        comparator(Op::GtEq, major, minor, patch, Vec::new())
+                    }
+                    None => comparator(Op::Gt, u64::MAX, u64::MAX, u64::MAX, Vec::new()),
+                };
+
+                (Some(lower), None)
+            }
+            Op::GtEq => (Some(lower(Op::GtEq)), None),
+            Op::Lt => (None, Some(lower(Op::Lt))),
+            Op::LtEq => (None, upper(successor(major, minor, None))),
+            Op::Tilde => (Some(lower(Op::GtEq)), upper(successor(major, minor, None))),
+            Op::Pessimistic => {
+                let bound = match patch {
+                    Some(_) => successor(major, minor, None),
+                    None => successor(major, None, None),
+                };
+
+                (Some(lower(Op::GtEq)), upper(bound))
+            }
+            Op::Compatible => {
+                let bound = match (minor, patch) {
+                    _ if major > 0 => successor(major, None, None),
+                    (None, _) => successor(major, None, None),
+                    (Some(minor), _) if minor > 0 => successor(major, Some(minor), None),
+                    (Some(minor), None) => successor(major, Some(minor), None),
+                    (Some(minor), Some(patch)) => successor(major, Some(minor), Some(patch)),
+                };
+
+                (Some(lower(Op::GtEq)), upper(bound))
+            }
+            Op::Wildcard(WildcardVersion::Major) => (Some(lower(Op::GtEq)), None),
+            Op::Wildcard(WildcardVersion::Minor) => {
+                (Some(lower(Op::GtEq)), upper(successor(major, None, None)))
+            }
+            Op::Wildcard(WildcardVersion::Patch) => {
+                (Some(lower(Op::GtEq)), upper(successor(major, minor, None)))
+            }
+        };
+
+        lower.into_iter().chain(upper).collect()
+    }
+
+    /// Lower this predicate into [`SimpleComparator`]s on complete versions.
+    ///
+    /// This is [`to_comparators`] with only plain comparison operators in the result, which is a
+    /// cleaner representation for interval math. Like [`bounds`], the comparators do not reflect
+    /// the rules on when prerelease versions match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range::{self, SimpleOp};
+    /// use semver_parser::version::Version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let p = range::parse_predicate("^1.2.3")?.expect("non-empty");
+    /// let comparators = p.to_simple_comparators();
+    ///
+    /// assert_eq!(SimpleOp::GtEq, comparators[0].op);
+    /// assert_eq!(Version::new(1, 2, 3), comparators[0].version);
+    /// assert_eq!(SimpleOp::Lt, comparators[1].op);
+    /// assert_eq!(Version::new(2, 0, 0), comparators[1].version);
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`SimpleComparator`]: ./struct.SimpleComparator.html
+    /// [`to_comparators`]: #method.to_comparators
+    /// [`bounds`]: #method.bounds
+    pub fn to_simple_comparators(&self) -> Vec<SimpleComparator> {
+        self.to_comparators()
+            .into_iter()
+            .filter_map(|c| {
+                let op = match c.op {
+                    Op::Ex => SimpleOp::Eq,
+                    Op::Gt => SimpleOp::Gt,
+                    Op::GtEq => SimpleOp::GtEq,
+                    Op::Lt => SimpleOp::Lt,
+                    Op::LtEq => SimpleOp::LtEq,
+                    // never produced by `to_comparators`.
+                    Op::Tilde | Op::Compatible | Op::Wildcard(_) | Op::Pessimistic => return None,
+                };
+
+                let version = Version {
+                    major: c.major,
+                    minor: c.minor.unwrap_or(0),
+                    patch: c.patch.unwrap_or(0),
+                    pre: c.pre,
+                    build: Vec::new(),
+                };
+
+                Some(SimpleComparator { op, version })
+            })
+            .collect()
+    }
+
+    /// Check if this predicate was written with an operator, to tell `1.2.3` from `^1.2.3` which
+    /// both have [`Op::Compatible`].
+    ///
+    /// This is read from [`raw`], so it is `false` for predicates which were not parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert!(!range::parse_predicate("1.2.3")?.expect("non-empty").explicit_op());
+    /// assert!(range::parse_predicate("^1.2.3")?.expect("non-empty").explicit_op());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`Op::Compatible`]: ./enum.Op.html#variant.Compatible
+    /// [`raw`]: #structfield.raw
+    pub fn explicit_op(&self) -> bool {
+        self.raw.starts_with(|c| "=<>~^".contains(c))
+    }
+
+    /// The character the wildcard of an `Op::Wildcard` predicate was written with, or `None` for
+    /// other predicates.
+    ///
+    /// This is read from [`raw`], so it is `None` for predicates which were not parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range::{self, WildcardShape};
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let p = range::parse_predicate("1.x")?.expect("non-empty");
+    /// assert_eq!(Some(WildcardShape::LowerX), p.wildcard());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`raw`]: #structfield.raw
+    pub fn wildcard(&self) -> Option<WildcardShape> {
+        match self.op {
+            // the version components before the wildcard are numeric.
+            Op::Wildcard(_) => self.raw.chars().find_map(|c| match c {
+                '*' => Some(WildcardShape::Star),
+                'x' => Some(WildcardShape::LowerX),
+                'X' => Some(WildcardShape::UpperX),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Format this predicate like `Display`, but writing a wildcard with the character it was
+    /// parsed with, as given by [`wildcard`].
+    ///
+    /// [`wildcard`]: #method.wildcard
+    pub fn to_faithful_string(&self) -> String {
+        Faithful::Predicate(self).to_string()
+    }
+
+    /// The lower and upper bounds of the versions this predicate allows.
+    ///
+    /// This is based on [`to_comparators`], so for example `~1.2.3` has the bounds
+    /// `(Included(1.2.3), Excluded(1.3.0))` and `>1.0.0` has `(Excluded(1.0.0), Unbounded)`.
+    /// The rules on when prerelease versions match are not reflected in the bounds.
+    ///
+    /// An upper bound past the greatest possible version is `Unbounded`, like for
+    /// `^18446744073709551615`, and a lower bound past it excludes the greatest version, so no
+    /// version falls within the bounds of `>18446744073709551615`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range;
+    /// use semver_parser::version::Version;
+    /// use std::ops::Bound;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let p = range::parse_predicate("^1.2")?.expect("non-empty");
+    ///
+    /// assert_eq!(
+    ///     (Bound::Included(Version::new(1, 2, 0)), Bound::Excluded(Version::new(2, 0, 0))),
+    ///     p.bounds()
+    /// );
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`to_comparators`]: #method.to_comparators
+    pub fn bounds(&self) -> (Bound<Version>, Bound<Version>) {
+        let mut lower = Bound::Unbounded;
+        let mut upper = Bound::Unbounded;
+
+        for c in self.to_comparators() {
+            let version = Version {
+                major: c.major,
+                minor: c.minor.unwrap_or(0),
+                patch: c.patch.unwrap_or(0),
+                pre: c.pre,
+                build: Vec::new(),
+            };
+
+            match c.op {
+                Op::Ex => {
+                    lower = Bound::Included(version.clone());
+                    upper = Bound::Included(version);
+                }
+                Op::Gt => lower = Bound::Excluded(version),
+                Op::GtEq => lower = Bound::Included(version),
+                Op::Lt => upper = Bound::Excluded(version),
+                Op::LtEq => upper = Bound::Included(version),
+                // never produced by `to_comparators`.
+                Op::Tilde | Op::Compatible | Op::Wildcard(_) | Op::Pessimistic => {}
+            }
+        }
+
+        (lower, upper)
+    }
+
+    /// The versions allowed by this predicate as a [`PredicateRange`], usable as `RangeBounds`.
+    ///
+    /// [`PredicateRange`]: ./struct.PredicateRange.html
+    pub fn to_range(&self) -> PredicateRange {
+        PredicateRange::from(self)
+    }
+
+    /// Find where the given [`version::Version`] lies relative to the [`bounds`] of this
+    /// predicate.
+    ///
+    /// This makes it possible to binary search a sorted list of versions for the ones allowed by
+    /// the predicate. Like the bounds, this does not reflect the rules on when prerelease versions
+    /// match, so a version classified as [`PredicateMatch::Matches`] might still not [`matches`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::range::{self, PredicateMatch};
+    /// use semver_parser::version::Version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let p = range::parse_predicate("~1.2")?.expect("non-empty");
+    ///
+    /// assert_eq!(PredicateMatch::Below, p.compare(&Version::new(1, 1, 9)));
+    /// assert_eq!(PredicateMatch::Matches, p.compare(&Version::new(1, 2, 5)));
+    /// assert_eq!(PredicateMatch::Above, p.compare(&Version::new(1, 3, 0)));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`version::Version`]: ../version/struct.Version.html
+    /// [`bounds`]: #method.bounds
+    /// [`PredicateMatch::Matches`]: ./enum.PredicateMatch.html#variant.Matches
+    /// [`matches`]: #method.matches
+    pub fn compare(&self, version: &Version) -> PredicateMatch {
+        let (lower, upper) = self.bounds();
+
+        let below = match lower {
+            Bound::Included(ref lower) => version::compare(version, lower) == Ordering::Less,
+            Bound::Excluded(ref lower) => version::compare(version, lower) != Ordering::Greater,
+            Bound::Unbounded => false,
+        };
+
+        let above = match upper {
+            Bound::Included(ref upper) => version::compare(version, upper) == Ordering::Greater,
+            Bound::Excluded(ref upper) => version::compare(version, upper) != Ordering::Less,
+            Bound::Unbounded => false,
+        };
+
+        if below {
+            PredicateMatch::Below
+        } else if above {
+            PredicateMatch::Above
+        } else {
+            PredicateMatch::Matches
+        }
+    }
+
+    /// Check the numeric parts of the version against this predicate, ignoring the rules on when
+    /// prerelease versions are allowed to match.
+    fn matches_numeric(&self, version: &Version) -> bool {
+        match self.op {
+            Op::Ex => self.matches_exact(version),
+            Op::Gt => self.matches_greater(version),
+            Op::GtEq => self.matches_exact(version) || self.matches_greater(version),
+            Op::Lt => !self.matches_exact(version) && !self.matches_greater(version),
+            Op::LtEq => !self.matches_greater(version),
+            Op::Tilde => self.matches_tilde(version),
+            Op::Pessimistic => self.matches_pessimistic(version),
+            Op::Compatible => self.matches_compatible(version),
+            Op::Wildcard(ref wildcard) => self.matches_wildcard(wildcard, version),
+        }
+    }
+
+    fn matches_exact(&self, version: &Version) -> bool {
+        if self.major != version.major {
+            return false;
+        }
+
+        match self.minor {
+            Some(minor) if minor != version.minor => return false,
+            Some(_) => {}
+            None => return true,
+        }
+
+        match self.patch {
+            Some(patch) if patch != version.patch => return false,
+            Some(_) => {}
+            None => return true,
+        }
+
+        self.pre == version.pre
+    }
+
+    fn matches_greater(&self, version: &Version) -> bool {
+        if self.major != version.major {
+            return version.major > self.major;
+        }
+
+        match self.minor {
+            Some(minor) if minor != version.minor => return version.minor > minor,
+            Some(_) => {}
+            None => return false,
+        }
+
+        match self.patch {
+            Some(patch) if patch != version.patch => return version.patch > patch,
+            Some(_) => {}
+            None => return false,
+        }
+
+        if !self.pre.is_empty() {
+            return version.pre.is_empty() || version.pre > self.pre;
+        }
+
+        false
+    }
+
+    fn matches_tilde(&self, version: &Version) -> bool {
+        if self.major != version.major {
+            return false;
+        }
+
+        let minor = match self.minor {
+            Some(minor) => minor,
+            None => return true,
+        };
+
+        if minor != version.minor {
+            return false;
+        }
+
+        match self.patch {
+            Some(patch) if patch != version.patch => version.patch > patch,
+            Some(_) => self.pre_is_compatible(version),
+            None => true,
+        }
+    }
+
+    fn matches_pessimistic(&self, version: &Version) -> bool {
+        if self.patch.is_some() {
+            return self.matches_tilde(version);
+        }
+
+        if self.major != version.major {
+            return false;
+        }
+
+        match self.minor {
+            Some(minor) => version.minor >= minor,
+            None => true,
+        }
+    }
+
+    fn matches_compatible(&self, version: &Version) -> bool {
+        if self.major != version.major {
+            return false;
+        }
+
+        let minor = match self.minor {
+            Some(minor) => minor,
+            None => return true,
+        };
+
+        let patch = match self.patch {
+            Some(patch) => patch,
+            None if self.major > 0 => return version.minor >= minor,
+            None => return version.minor == minor,
+        };
+
+        if self.major > 0 {
+            if version.minor != minor {
+                version.minor > minor
+            } else if version.patch != patch {
+                version.patch > patch
+            } else {
+                self.pre_is_compatible(version)
+            }
+        } else if minor > 0 {
+            if version.minor != minor {
+                false
+            } else if version.patch != patch {
+                version.patch > patch
+            } else {
+                self.pre_is_compatible(version)
+            }
+        } else {
+            version.minor == minor && version.patch == patch && self.pre_is_compatible(version)
+        }
+    }
+
+    fn matches_wildcard(&self, wildcard: &WildcardVersion, version: &Version) -> bool {
+        match *wildcard {
+            WildcardVersion::Major => true,
+            WildcardVersion::Minor => self.major == version.major,
+            WildcardVersion::Patch => {
+                self.major == version.major && self.minor == Some(version.minor)
+            }
+        }
+    }
+
+    /// Check if the prerelease of the version is at least the prerelease of this predicate.
+    fn pre_is_compatible(&self, version: &Version) -> bool {
+        version.pre.is_empty() || version.pre >= self.pre
+    }
+
+    /// A prerelease version is only allowed to match if this predicate names the same
+    /// `major.minor.patch` and has a prerelease of its own.
+    ///
+    /// Missing components are zero here, the prerelease of a partial predicate like `0-Foo`
+    /// applies to `0.0.0-Foo`, the same as with `version::parse_partial`.
+    fn pre_tag_is_compatible(&self, version: &Version) -> bool {
+        if !version.is_prerelease() {
+            return true;
+        }
+
+        if self.major != version.major || self.minor.unwrap_or(0) != version.minor ||
+            self.patch.unwrap_or(0) != version.patch || !self.is_prerelease()
+        {
+            return false;
+        }
+
+        match self.op {
+            // the numeric check of complete predicates already covers this, partial ones do not
+            // look at the prerelease.
+            Op::Ex | Op::GtEq | Op::Tilde | Op::Compatible | Op::Pessimistic => {
+                self.pre_is_compatible(version)
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Builder for a [`Predicate`], created by [`Predicate::builder`].
 ///
-/// # fn try_main() -> Result<(), String> {
-/// let p = range::parse_predicate(">=1.1")?.expect("non-empty");
-/// assert_eq!(p.op, range::Op::GtEq);
-/// assert_eq!(p.major, 1);
-/// assert_eq!(p.minor.unwrap(), 1);
-/// assert!(p.patch.is_none());
-/// assert!(p.pre.is_empty());
+/// Components which are not set are left out, like in `^1`, and the prerelease and build metadata
+/// default to empty.
 ///
-/// let f = range::parse_predicate("not-a-version-predicate");
-/// assert!(f.is_err());
-/// # Ok(())
-/// # }
-/// #
-/// # fn main() {
-/// #   try_main().unwrap();
-/// # }
-/// ```
 /// [`Predicate`]: ./struct.Predicate.html
-pub fn parse_predicate<'input>(
-    input: &'input str,
-) -> Result<Option<Predicate>, parser::Error<'input>> {
-    let mut parser = Parser::new(input)?;
-    let predicate = parser.predicate()?;
+/// [`Predicate::builder`]: ./struct.Predicate.html#method.builder
+#[derive(Clone, Debug)]
+pub struct PredicateBuilder {
+    predicate: Predicate,
+}
 
-    if !parser.is_eof() {
-        return Err(parser::Error::MoreInput(parser.tail()?));
+impl PredicateBuilder {
+    /// Set the minor version.
+    pub fn minor(mut self, minor: u64) -> PredicateBuilder {
+        self.predicate.minor = Some(minor);
+        self
     }
 
-    Ok(predicate)
+    /// Set the patch version.
+    pub fn patch(mut self, patch: u64) -> PredicateBuilder {
+        self.predicate.patch = Some(patch);
+        self
+    }
+
+    /// Set the prerelease identifiers.
+    pub fn pre(mut self, pre: Vec<Identifier>) -> PredicateBuilder {
+        self.predicate.pre = pre;
+        self
+    }
+
+    /// Set the build metadata identifiers.
+    pub fn build_metadata(mut self, build: Vec<Identifier>) -> PredicateBuilder {
+        self.predicate.build = build;
+        self
+    }
+
+    /// Finish building the predicate.
+    pub fn build(self) -> Predicate {
+        self.predicate
+    }
 }
 
-/// Function for parsing [`VersionReq`] from string.
+/// The versions allowed by a single [`Predicate`], as a pair of bounds.
 ///
-/// Function for parsing [`VersionReq`] from string to `Result<`[`VersionReq`]`, String>`,
-/// where `Err` will contain error message in case of failed parsing.
+/// This implements `RangeBounds<Version>`, so it can be used with APIs like `BTreeMap::range`.
+/// Only single predicates can be represented like this, since the groups of a [`VersionReq`]
+/// are not necessarily contiguous. Like [`Predicate::bounds`], this does not reflect the rules on
+/// when prerelease versions match.
 ///
-/// # Examples
+/// The bounds never have build metadata, and are compared using the `Ord` of [`Version`], which
+/// sorts `1.2.3+build` right after `1.2.3`. So unlike [`Predicate::matches_prerelease`], the range
+/// of `>1.2.3` contains `1.2.3+build` and the range of `<=1.2.3` does not.
 ///
-/// Simple single-predicate [`VersionReq`]:
+/// # Examples
 ///
 /// ```
 /// use semver_parser::range;
+/// use semver_parser::version::Version;
+/// use std::collections::BTreeSet;
 ///
 /// # fn try_main() -> Result<(), String> {
-/// let r = range::parse("1.0.0")?;
+/// let versions: BTreeSet<_> = vec![
+///     Version::new(1, 1, 0),
+///     Version::new(1, 2, 5),
+///     Version::new(1, 3, 0),
+/// ].into_iter().collect();
 ///
-/// assert_eq!(range::Predicate {
-///         op: range::Op::Compatible,
-///         major: 1,
-///         minor: Some(0),
-///         patch: Some(0),
-///         pre: Vec::new(),
-///     },
-///     r.predicates[0]
-/// );
+/// let p = range::parse_predicate("~1.2")?.expect("non-empty");
+/// let matching: Vec<_> = versions.range(p.to_range()).collect();
+///
+/// assert_eq!(vec![&Version::new(1, 2, 5)], matching);
 /// # Ok(())
 /// # }
 /// #
@@ -336,21 +2649,305 @@ pub fn parse_predicate<'input>(
 /// #   try_main().unwrap();
 /// # }
 /// ```
+/// [`Predicate`]: ./struct.Predicate.html
+/// [`VersionReq`]: ./struct.VersionReq.html
+/// [`Predicate::bounds`]: ./struct.Predicate.html#method.bounds
+/// [`Version`]: ../version/struct.Version.html
+/// [`Predicate::matches_prerelease`]: ./struct.Predicate.html#method.matches_prerelease
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PredicateRange {
+    start: Bound<Version>,
+    end: Bound<Version>,
+}
+
+impl RangeBounds<Version> for PredicateRange {
+    fn start_bound(&self) -> Bound<&Version> {
+        bound_as_ref(&self.start)
+    }
+
+    fn end_bound(&self) -> Bound<&Version> {
+        bound_as_ref(&self.end)
+    }
+}
+
+impl<'a> From<&'a Predicate> for PredicateRange {
+    fn from(predicate: &'a Predicate) -> PredicateRange {
+        let (start, end) = predicate.bounds();
+        PredicateRange { start, end }
+    }
+}
+
+/// Where a version lies relative to the bounds of a [`Predicate`], as returned by
+/// [`Predicate::compare`].
 ///
-/// Multiple predicates in [`VersionReq`]:
-///
-/// ```
-/// use semver_parser::range;
+/// [`Predicate`]: ./struct.Predicate.html
+/// [`Predicate::compare`]: ./struct.Predicate.html#method.compare
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PredicateMatch {
+    /// The version is below the lower bound.
+    Below,
+    /// The version is within the bounds.
+    Matches,
+    /// The version is above the upper bound.
+    Above,
+}
+
+/// A plain comparison operator, as used by a [`SimpleComparator`].
 ///
-/// # fn try_main() -> Result<(), String> {
-/// let r = range::parse("> 0.0.9, <= 2.5.3")?;
+/// Unlike [`Op`], this has no operators with cargo specific meaning like `~` or `^`.
 ///
-/// assert_eq!(range::Predicate {
-///         op: range::Op::Gt,
-///         major: 0,
-///         minor: Some(0),
-///         patch: Some(9),
+/// [`SimpleComparator`]: ./struct.SimpleComparator.html
+/// [`Op`]: ./enum.Op.html
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SimpleOp {
+    /// Exact, `=`.
+    Eq,
+    /// Greater than, `>`.
+    Gt,
+    /// Greater than or equal to, `>=`.
+    GtEq,
+    /// Less than, `<`.
+    Lt,
+    /// Less than or equal to, `<=`.
+    LtEq,
+}
+
+impl SimpleOp {
+    /// The operator token for this `SimpleOp`, like `">="` for [`SimpleOp::GtEq`].
+    ///
+    /// [`SimpleOp::GtEq`]: #variant.GtEq
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            SimpleOp::Eq => "=",
+            SimpleOp::Gt => ">",
+            SimpleOp::GtEq => ">=",
+            SimpleOp::Lt => "<",
+            SimpleOp::LtEq => "<=",
+        }
+    }
+}
+
+impl fmt::Display for SimpleOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<SimpleOp> for Op {
+    fn from(value: SimpleOp) -> Op {
+        match value {
+            SimpleOp::Eq => Op::Ex,
+            SimpleOp::Gt => Op::Gt,
+            SimpleOp::GtEq => Op::GtEq,
+            SimpleOp::Lt => Op::Lt,
+            SimpleOp::LtEq => Op::LtEq,
+        }
+    }
+}
+
+/// A plain comparison against a complete version, like `>=1.2.3`, as produced by
+/// [`Predicate::to_simple_comparators`].
+///
+/// This is not to be confused with [`comparator::Comparator`], which is a set of ranges.
+///
+/// [`Predicate::to_simple_comparators`]: ./struct.Predicate.html#method.to_simple_comparators
+/// [`comparator::Comparator`]: ../comparator/struct.Comparator.html
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SimpleComparator {
+    /// The comparison operator.
+    pub op: SimpleOp,
+    /// The version to compare against.
+    pub version: Version,
+}
+
+impl SimpleComparator {
+    /// Check if the given [`version::Version`] compares as required against the version of this
+    /// comparator, by semver precedence.
+    ///
+    /// There are no special rules for prerelease versions, so `<2.0.0` matches `2.0.0-alpha`.
+    ///
+    /// [`version::Version`]: ../version/struct.Version.html
+    pub fn matches(&self, version: &Version) -> bool {
+        let ordering = version::compare(version, &self.version);
+
+        match self.op {
+            SimpleOp::Eq => ordering == Ordering::Equal,
+            SimpleOp::Gt => ordering == Ordering::Greater,
+            SimpleOp::GtEq => ordering != Ordering::Less,
+            SimpleOp::Lt => ordering == Ordering::Less,
+            SimpleOp::LtEq => ordering != Ordering::Greater,
+        }
+    }
+}
+
+impl fmt::Display for SimpleComparator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.op, self.version)
+    }
+}
+
+/// Borrow the version of a bound.
+fn bound_as_ref(bound: &Bound<Version>) -> Bound<&Version> {
+    match *bound {
+        Bound::Included(ref v) => Bound::Included(v),
+        Bound::Excluded(ref v) => Bound::Excluded(v),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+/// Construct a predicate comparing against a complete version.
+fn comparator(op: Op, major: u64, minor: u64, patch: u64, pre: Vec<Identifier>) -> Predicate {
+    Predicate {
+        op,
+        major,
+        minor: Some(minor),
+        patch: Some(patch),
+        pre,
+        build: Vec::new(),
+        raw: String::new(),
+    }
+}
+
+/// The smallest version above all versions starting with the given components.
+///
+/// Like, `1.3.0` for `1.2` or `1.2.4` for `1.2.3`. Returns `None` if there is no such version
+/// because the components are at their maximum.
+fn successor(major: u64, minor: Option<u64>, patch: Option<u64>) -> Option<(u64, u64, u64)> {
+    match (minor, patch) {
+        (Some(minor), Some(patch)) => match patch.checked_add(1) {
+            Some(patch) => Some((major, minor, patch)),
+            None => successor(major, Some(minor), None),
+        },
+        (Some(minor), None) => match minor.checked_add(1) {
+            Some(minor) => Some((major, minor, 0)),
+            None => successor(major, None, None),
+        },
+        (None, _) => major.checked_add(1).map(|major| (major, 0, 0)),
+    }
+}
+
+/// The greatest release below the given version, ignoring its prerelease.
+///
+/// Like, `1.2.2` for `1.2.3` or `1.1.18446744073709551615` for `1.2.0`. Returns `None` for
+/// `0.0.0`.
+fn predecessor(major: u64, minor: u64, patch: u64) -> Option<(u64, u64, u64)> {
+    if patch > 0 {
+        Some((major, minor, patch - 1))
+    } else if minor > 0 {
+        Some((major, minor - 1, u64::MAX))
+    } else if major > 0 {
+        Some((major - 1, u64::MAX, u64::MAX))
+    } else {
+        None
+    }
+}
+
+/// Function parsing [`Predicate`] from string.
+///
+/// Function parsing [`Predicate`] from string to `Result<`[`Predicate`]`, String>`,
+/// where `Err` will contain error message in case of failed parsing.
+///
+/// # Examples
+///
+/// Parsing [`Predicate`] from string and cheking its fields:
+///
+/// ```
+/// use semver_parser::range;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let p = range::parse_predicate(">=1.1")?.expect("non-empty");
+/// assert_eq!(p.op, range::Op::GtEq);
+/// assert_eq!(p.major, 1);
+/// assert_eq!(p.minor.unwrap(), 1);
+/// assert!(p.patch.is_none());
+/// assert!(p.pre.is_empty());
+///
+/// let f = range::parse_predicate("not-a-version-predicate");
+/// assert!(f.is_err());
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`Predicate`]: ./struct.Predicate.html
+pub fn parse_predicate<'input>(
+    input: &'input str,
+) -> Result<Option<Predicate>, parser::Error<'input>> {
+    let mut parser = Parser::new(input)?;
+    let predicate = parser.predicate()?;
+
+    if !parser.is_eof() {
+        let position = parser.position();
+        return Err(parser::Error::MoreInput(parser.tail()?, position));
+    }
+
+    Ok(predicate)
+}
+
+/// Function for parsing [`VersionReq`] from string.
+///
+/// Function for parsing [`VersionReq`] from string to `Result<`[`VersionReq`]`, String>`,
+/// where `Err` will contain error message in case of failed parsing.
+///
+/// Predicates which must all match are separated by commas like in cargo, or only by whitespace
+/// like in node-semver, so `>=1.2.3, <2.0.0` and `>=1.2.3 <2.0.0` are the same. Groups of
+/// predicates are separated by `||`, which binds looser than both separators: `>=1.2.3 <2.0.0 ||
+/// ^3` is `(>=1.2.3 and <2.0.0) or ^3`.
+///
+/// Whitespace, including tabs, is allowed between an operator and its version, like `>= 1.0.0`,
+/// but not inside an operator, so `> = 1.0.0` is an error.
+///
+/// # Examples
+///
+/// Simple single-predicate [`VersionReq`]:
+///
+/// ```
+/// use semver_parser::range;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let r = range::parse("1.0.0")?;
+///
+/// assert_eq!(range::Predicate {
+///         op: range::Op::Compatible,
+///         major: 1,
+///         minor: Some(0),
+///         patch: Some(0),
+///         pre: Vec::new(),
+///         build: Vec::new(),
+///         raw: String::from("1.0.0"),
+///     },
+///     r.predicates[0]
+/// );
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+///
+/// Multiple predicates in [`VersionReq`]:
+///
+/// ```
+/// use semver_parser::range;
+///
+/// # fn try_main() -> Result<(), String> {
+/// assert_eq!(range::parse("> 0.0.9 <= 2.5.3")?, range::parse("> 0.0.9, <= 2.5.3")?);
+///
+/// let r = range::parse("> 0.0.9, <= 2.5.3")?;
+///
+/// assert_eq!(range::Predicate {
+///         op: range::Op::Gt,
+///         major: 0,
+///         minor: Some(0),
+///         patch: Some(9),
 ///         pre: Vec::new(),
+///         build: Vec::new(),
+///         raw: String::from("> 0.0.9"),
 ///     },
 ///     r.predicates[0]
 /// );
@@ -361,6 +2958,8 @@ pub fn parse_predicate<'input>(
 ///         minor: Some(5),
 ///         patch: Some(3),
 ///         pre: Vec::new(),
+///         build: Vec::new(),
+///         raw: String::from("<= 2.5.3"),
 ///     },
 ///     r.predicates[1]
 /// );
@@ -370,70 +2969,704 @@ pub fn parse_predicate<'input>(
 /// # fn main() {
 /// #   try_main().unwrap();
 /// # }
+/// ```
 /// [`VersionReq`]: ./struct.VersionReq.html
 pub fn parse<'input>(input: &'input str) -> Result<VersionReq, parser::Error<'input>> {
     let mut parser = Parser::new(input)?;
     let range = parser.range()?;
 
     if !parser.is_eof() {
-        return Err(parser::Error::MoreInput(parser.tail()?));
+        let position = parser.position();
+        return Err(parser::Error::MoreInput(parser.tail()?, position));
     }
 
     Ok(range)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use range;
-    use version::Identifier;
+/// Check if the input is a valid range, accepted by [`parse`].
+///
+/// This does not build the [`VersionReq`], every predicate is dropped right after parsing it.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::range;
+///
+/// assert!(range::is_valid(">=1.2.3, <2 || 3.*"));
+/// assert!(!range::is_valid(">=1.2.3,"));
+/// assert!(!range::is_valid("<*"));
+/// ```
+/// [`parse`]: ./fn.parse.html
+/// [`VersionReq`]: ./struct.VersionReq.html
+pub fn is_valid(input: &str) -> bool {
+    let mut parser = match Parser::new(input) {
+        Ok(parser) => parser,
+        Err(_) => return false,
+    };
 
-    #[test]
-    fn test_parsing_wildcards() {
-        assert_eq!(
-            Op::Wildcard(WildcardVersion::Patch),
-            range::parse("1.0.*").unwrap().predicates[0].op
-        );
-        assert_eq!(
-            Op::Wildcard(WildcardVersion::Patch),
-            range::parse("1.*.*").unwrap().predicates[0].op
-        );
-        assert_eq!(
-            Op::Wildcard(WildcardVersion::Minor),
-            parse("1.*.0").unwrap().predicates[0].op
-        );
-    }
+    parser.validate_range().is_ok() && parser.is_eof()
+}
 
-    #[test]
-    fn test_parsing_default() {
-        let r = range::parse("1.0.0").unwrap();
+/// Function for parsing a [`VersionReq`] from bytes.
+///
+/// Works like [`parse`] after checking that the input is valid UTF-8. Invalid UTF-8 is reported
+/// as an unexpected character at the offset of the first invalid byte.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::range;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let r = range::parse_bytes(b">=1.2.3, <2.0.0")?;
+/// assert_eq!(range::parse(">=1.2.3, <2.0.0")?, r);
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`VersionReq`]: ./struct.VersionReq.html
+/// [`parse`]: ./fn.parse.html
+pub fn parse_bytes(input: &[u8]) -> Result<VersionReq, parser::Error<'_>> {
+    parse(str::from_utf8(input)?)
+}
 
-        assert_eq!(
-            Predicate {
-                op: Op::Compatible,
-                major: 1,
-                minor: Some(0),
-                patch: Some(0),
-                pre: Vec::new(),
-            },
-            r.predicates[0]
-        );
+/// Function for leniently parsing a [`VersionReq`] from hand-written or generated input.
+///
+/// Works like [`parse`], but skips empty comma-separated predicates and leading whitespace, like
+/// in ` >=1.0.0, , <2.0.0,`, which [`parse`] rejects. A group after `||` still needs at least
+/// one predicate.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::range;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let r = range::parse_relaxed(">=1.0.0, ")?;
+///
+/// assert_eq!(range::parse(">=1.0.0")?, r);
+/// assert!(range::parse(">=1.0.0, ").is_err());
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`VersionReq`]: ./struct.VersionReq.html
+/// [`parse`]: ./fn.parse.html
+pub fn parse_relaxed<'input>(input: &'input str) -> Result<VersionReq, parser::Error<'input>> {
+    let mut parser = Parser::new(input)?;
+    parser.set_relaxed(true);
+    let range = parser.range()?;
+
+    if !parser.is_eof() {
+        let position = parser.position();
+        return Err(parser::Error::MoreInput(parser.tail()?, position));
     }
 
-    #[test]
-    fn test_parsing_exact_01() {
-        let r = range::parse("=1.0.0").unwrap();
+    Ok(range)
+}
 
-        assert_eq!(
-            Predicate {
-                op: Op::Ex,
-                major: 1,
-                minor: Some(0),
-                patch: Some(0),
-                pre: Vec::new(),
-            },
-            r.predicates[0]
-        );
+/// Function for parsing a [`VersionReq`], requiring all three version components before a
+/// prerelease or build metadata.
+///
+/// Works like [`parse`], which also accepts a prerelease on a partial version like `0-Foo` or
+/// `1.2-rc`, except that such predicates are rejected.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::range;
+///
+/// # fn try_main() -> Result<(), String> {
+/// assert_eq!(range::parse(">=1.2.0-rc, <2")?, range::parse_strict(">=1.2.0-rc, <2")?);
+/// assert!(range::parse_strict(">=1.2-rc").is_err());
+/// assert!(range::parse(">=1.2-rc").is_ok());
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`VersionReq`]: ./struct.VersionReq.html
+/// [`parse`]: ./fn.parse.html
+pub fn parse_strict<'input>(input: &'input str) -> Result<VersionReq, parser::Error<'input>> {
+    let mut parser = Parser::new(input)?;
+    parser.set_strict(true);
+    let range = parser.range()?;
+
+    if !parser.is_eof() {
+        let position = parser.position();
+        return Err(parser::Error::MoreInput(parser.tail()?, position));
+    }
+
+    Ok(range)
+}
+
+/// Function for parsing a [`VersionReq`], keeping a bare `*` as a predicate.
+///
+/// Works like [`parse`], except that `*` becomes a [`WildcardVersion::Major`] predicate instead
+/// of being left out, so that all three wildcard levels are represented the same way.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::range::{self, Op, WildcardVersion};
+///
+/// # fn try_main() -> Result<(), String> {
+/// let r = range::parse_explicit_wildcard("*")?;
+///
+/// assert_eq!(Op::Wildcard(WildcardVersion::Major), r.predicates[0].op);
+/// assert!(range::parse("*")?.predicates.is_empty());
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`VersionReq`]: ./struct.VersionReq.html
+/// [`WildcardVersion::Major`]: ./enum.WildcardVersion.html#variant.Major
+/// [`parse`]: ./fn.parse.html
+pub fn parse_explicit_wildcard<'input>(
+    input: &'input str,
+) -> Result<VersionReq, parser::Error<'input>> {
+    let mut parser = Parser::new(input)?;
+    parser.set_explicit_major_wildcard(true);
+    let range = parser.range()?;
+
+    if !parser.is_eof() {
+        let position = parser.position();
+        return Err(parser::Error::MoreInput(parser.tail()?, position));
+    }
+
+    Ok(range)
+}
+
+/// Function for parsing a [`VersionReq`] off the front of a string.
+///
+/// Returns the requirement and the rest of the input following it, so that it can be embedded in
+/// a larger grammar. Parsing stops before the first part of the input which does not belong to
+/// the requirement, including any whitespace before it.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::range;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let (r, rest) = range::parse_prefix(">=1.2.3, <2.0.0 rest-of-input")?;
+///
+/// assert_eq!(">=1.2.3, <2.0.0", r.to_string());
+/// assert_eq!(" rest-of-input", rest);
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`VersionReq`]: ./struct.VersionReq.html
+pub fn parse_prefix<'input>(
+    input: &'input str,
+) -> Result<(VersionReq, &'input str), parser::Error<'input>> {
+    let mut parser = Parser::new(input)?;
+    let range = parser.range_prefix()?;
+
+    Ok((range, parser.remaining()))
+}
+
+/// Function for parsing a [`VersionReq`], collecting as many errors as possible instead of
+/// stopping at the first one.
+///
+/// The input is split on `||` and commas, and every predicate is parsed separately, so that an
+/// invalid or empty predicate does not hide problems in the ones after it. Byte offsets in the
+/// errors are relative to the whole input. Returns the requirement like [`parse`] if there are
+/// no errors, and `None` otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::range;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let (r, errors) = range::parse_collect_errors(">=1.2.3, <2");
+/// assert_eq!(Some(range::parse(">=1.2.3, <2")?), r);
+/// assert!(errors.is_empty());
+///
+/// let (r, errors) = range::parse_collect_errors(">=1.02, , <2 || ^x.1");
+/// assert_eq!(None, r);
+/// assert_eq!(3, errors.len());
+/// assert_eq!(Some(4), errors[0].position());
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`VersionReq`]: ./struct.VersionReq.html
+/// [`parse`]: ./fn.parse.html
+pub fn parse_collect_errors(input: &str) -> (Option<VersionReq>, Vec<ParseError>) {
+    let mut errors = Vec::new();
+
+    if !input.trim().is_empty() {
+        for (group_start, group) in split_with_offsets(input, "||") {
+            if group.trim().is_empty() {
+                errors.push(ParseError::from(parser::Error::EmptyRange));
+                continue;
+            }
+
+            let predicates = split_with_offsets(group, ",");
+            let count = predicates.len();
+
+            for (i, (start, predicate)) in predicates.into_iter().enumerate() {
+                let start = group_start + start;
+
+                if predicate.trim().is_empty() {
+                    // report the comma without a predicate, like `parse` does.
+                    let comma = if i > 0 { start - 1 } else { start + predicate.len() };
+
+                    if count > 1 {
+                        errors.push(ParseError::from(parser::Error::EmptyPredicate(comma)));
+                    }
+
+                    continue;
+                }
+
+                // the parser does not skip leading whitespace.
+                let trimmed = predicate.trim_start();
+                let start = start + predicate.len() - trimmed.len();
+
+                if let Err(error) = parse(trimmed) {
+                    errors.push(ParseError::from(offset_error(error, start)));
+                }
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return (None, errors);
+    }
+
+    // the separate predicates are fine, let the parser check the input as a whole.
+    match parse(input) {
+        Ok(range) => (Some(range), errors),
+        Err(error) => (None, vec![ParseError::from(error)]),
+    }
+}
+
+/// Split the input on the given separator, along with the byte offset of each part.
+fn split_with_offsets<'a>(input: &'a str, separator: &str) -> Vec<(usize, &'a str)> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+
+    for (i, _) in input.match_indices(separator) {
+        parts.push((start, &input[start..i]));
+        start = i + separator.len();
+    }
+
+    parts.push((start, &input[start..]));
+    parts
+}
+
+/// Move the byte offset of an error from parsing a part of the input starting at `offset`.
+fn offset_error(error: parser::Error<'_>, offset: usize) -> parser::Error<'_> {
+    use parser::Error::*;
+
+    match error {
+        UnexpectedToken(token, position) => UnexpectedToken(token, position + offset),
+        Lexer(lexer::Error::UnexpectedChar(c, position)) => {
+            Lexer(lexer::Error::UnexpectedChar(c, position + offset))
+        }
+        Lexer(lexer::Error::IllegalCharacter(c, position)) => {
+            Lexer(lexer::Error::IllegalCharacter(c, position + offset))
+        }
+        MoreInput(tokens, position) => MoreInput(tokens, position + offset),
+        EmptyPredicate(position) => EmptyPredicate(position + offset),
+        UnexpectedOperator(first, second, position) => {
+            UnexpectedOperator(first, second, position + offset)
+        }
+        LeadingZero(number, position) => LeadingZero(number, position + offset),
+        EmptyIdentifier(position) => EmptyIdentifier(position + offset),
+        LimitExceeded(limit, position) => LimitExceeded(limit, position + offset),
+        WildcardAfterOperator(op, position) => WildcardAfterOperator(op, position + offset),
+        error => error,
+    }
+}
+
+/// Parse one requirement per line, like in a constraints file.
+///
+/// Returns every requirement with its line number, starting at 1. Everything after a `#` is a
+/// comment, and lines which are blank apart from comments are skipped. Each line is parsed on its
+/// own, so an invalid line yields an error without affecting the others. Positions in the errors
+/// are relative to the start of the requirement on its line.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::range;
+///
+/// let results = range::parse_lines("# constraints\n^1.2\n\n>=2, <3 # pinned\n>=1.02\n");
+///
+/// assert_eq!(vec![2, 4, 5], results.iter().map(|&(line, _)| line).collect::<Vec<_>>());
+/// assert_eq!(range::parse(">=2, <3").ok(), results[1].1.clone().ok());
+/// assert!(results[2].1.is_err());
+/// ```
+pub fn parse_lines(input: &str) -> Vec<(usize, Result<VersionReq, ParseError>)> {
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line = match line.find('#') {
+                Some(comment) => &line[..comment],
+                None => line,
+            };
+            let line = line.trim();
+
+            if line.is_empty() {
+                return None;
+            }
+
+            Some((i + 1, parse(line).map_err(ParseError::from)))
+        })
+        .collect()
+}
+
+impl FromStr for VersionReq {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<VersionReq, ParseError> {
+        parse(s).map_err(ParseError::from)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for VersionReq {
+    type Error = ParseError;
+
+    fn try_from(s: &'a str) -> Result<VersionReq, ParseError> {
+        s.parse()
+    }
+}
+
+/// Serializes the requirement as its canonical string, like `">=1.0.0, <2.0.0"`.
+#[cfg(feature = "serde")]
+impl Serialize for VersionReq {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserializes the requirement from a string using [`parse`].
+///
+/// [`parse`]: ./fn.parse.html
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for VersionReq {
+    fn deserialize<D>(deserializer: D) -> Result<VersionReq, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let input = String::deserialize(deserializer)?;
+        parse(&input).map_err(de::Error::custom)
+    }
+}
+
+/// Converts a requirement of the [`semver`](https://docs.rs/semver) crate, mapping its
+/// comparators to predicates one by one.
+///
+/// This fails for operators added to `semver::Op` that this crate does not know, and for
+/// prerelease identifiers this crate does not accept.
+#[cfg(feature = "semver-compat")]
+impl<'a> TryFrom<&'a semver::VersionReq> for VersionReq {
+    type Error = String;
+
+    fn try_from(req: &'a semver::VersionReq) -> Result<VersionReq, String> {
+        let predicates = req
+            .comparators
+            .iter()
+            .map(Predicate::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(VersionReq {
+            predicates,
+            or_groups: Vec::new(),
+        })
+    }
+}
+
+/// Converts the requirement into one of the [`semver`](https://docs.rs/semver) crate.
+///
+/// This fails for requirements with `||` and for `~>` predicates, which `semver` does not
+/// support. A `*` predicate is left out like `semver` does, and build metadata is dropped.
+#[cfg(feature = "semver-compat")]
+impl<'a> TryFrom<&'a VersionReq> for semver::VersionReq {
+    type Error = String;
+
+    fn try_from(req: &'a VersionReq) -> Result<semver::VersionReq, String> {
+        if !req.or_groups.is_empty() {
+            return Err(String::from("Alternative requirements are not supported by semver"));
+        }
+
+        let comparators = req
+            .predicates
+            .iter()
+            .filter(|predicate| predicate.op != Op::Wildcard(WildcardVersion::Major))
+            .map(semver::Comparator::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(semver::VersionReq { comparators })
+    }
+}
+
+/// Converts a comparator of the [`semver`](https://docs.rs/semver) crate, `semver::Op::Caret`
+/// becomes [`Op::Compatible`] and `semver::Op::Exact` becomes [`Op::Ex`].
+///
+/// [`Op::Compatible`]: ./enum.Op.html#variant.Compatible
+/// [`Op::Ex`]: ./enum.Op.html#variant.Ex
+#[cfg(feature = "semver-compat")]
+impl<'a> TryFrom<&'a semver::Comparator> for Predicate {
+    type Error = String;
+
+    fn try_from(comparator: &'a semver::Comparator) -> Result<Predicate, String> {
+        let op = match comparator.op {
+            semver::Op::Exact => Op::Ex,
+            semver::Op::Greater => Op::Gt,
+            semver::Op::GreaterEq => Op::GtEq,
+            semver::Op::Less => Op::Lt,
+            semver::Op::LessEq => Op::LtEq,
+            semver::Op::Tilde => Op::Tilde,
+            semver::Op::Caret => Op::Compatible,
+            semver::Op::Wildcard if comparator.minor.is_none() => {
+                Op::Wildcard(WildcardVersion::Minor)
+            }
+            semver::Op::Wildcard => Op::Wildcard(WildcardVersion::Patch),
+            _ => return Err(String::from("Could not convert Op")),
+        };
+
+        // the identifiers are parsed on their own, the version numbers are not used
+        let pre = Version::from_parts(0, 0, 0, comparator.pre.as_str(), "")
+            .map_err(|e| e.to_string())?
+            .pre;
+
+        Ok(Predicate {
+            op,
+            major: comparator.major,
+            minor: comparator.minor,
+            patch: comparator.patch,
+            pre,
+            build: Vec::new(),
+            raw: String::new(),
+        })
+    }
+}
+
+/// Converts the predicate into a comparator of the [`semver`](https://docs.rs/semver) crate.
+///
+/// This fails for [`Op::Pessimistic`] and for a `*` predicate, which `semver` has no comparator
+/// for. Build metadata is dropped.
+///
+/// [`Op::Pessimistic`]: ./enum.Op.html#variant.Pessimistic
+#[cfg(feature = "semver-compat")]
+impl<'a> TryFrom<&'a Predicate> for semver::Comparator {
+    type Error = String;
+
+    fn try_from(predicate: &'a Predicate) -> Result<semver::Comparator, String> {
+        let (op, minor, patch) = match predicate.op {
+            Op::Ex => (semver::Op::Exact, predicate.minor, predicate.patch),
+            Op::Gt => (semver::Op::Greater, predicate.minor, predicate.patch),
+            Op::GtEq => (semver::Op::GreaterEq, predicate.minor, predicate.patch),
+            Op::Lt => (semver::Op::Less, predicate.minor, predicate.patch),
+            Op::LtEq => (semver::Op::LessEq, predicate.minor, predicate.patch),
+            Op::Tilde => (semver::Op::Tilde, predicate.minor, predicate.patch),
+            Op::Compatible => (semver::Op::Caret, predicate.minor, predicate.patch),
+            Op::Wildcard(WildcardVersion::Minor) => (semver::Op::Wildcard, None, None),
+            Op::Wildcard(WildcardVersion::Patch) => (semver::Op::Wildcard, predicate.minor, None),
+            Op::Wildcard(WildcardVersion::Major) | Op::Pessimistic => {
+                return Err(format!("Op {} is not supported by semver", predicate.op));
+            }
+        };
+
+        let pre: Vec<_> = predicate.pre.iter().map(ToString::to_string).collect();
+
+        Ok(semver::Comparator {
+            op,
+            major: predicate.major,
+            minor,
+            patch,
+            pre: semver::Prerelease::new(&pre.join(".")).map_err(|e| e.to_string())?,
+        })
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with(f, |predicate, f| write!(f, "{}", predicate))
+    }
+}
+
+impl VersionReq {
+    /// Write this requirement like `Display`, writing each predicate with the given function.
+    fn fmt_with<F>(&self, f: &mut fmt::Formatter, mut predicate_fmt: F) -> fmt::Result
+    where
+        F: FnMut(&Predicate, &mut fmt::Formatter) -> fmt::Result,
+    {
+        for (i, predicates) in self.groups().enumerate() {
+            if i > 0 {
+                write!(f, " || ")?;
+            }
+
+            if predicates.is_empty() {
+                write!(f, "*")?;
+            }
+
+            for (j, predicate) in predicates.iter().enumerate() {
+                if j > 0 {
+                    write!(f, ", ")?;
+                }
+
+                predicate_fmt(predicate, f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a [`VersionReq`] or a [`Predicate`] with the wildcard characters they were parsed with,
+/// as returned by [`VersionReq::to_faithful_string`] and [`Predicate::to_faithful_string`].
+///
+/// [`VersionReq`]: ./struct.VersionReq.html
+/// [`Predicate`]: ./struct.Predicate.html
+/// [`VersionReq::to_faithful_string`]: ./struct.VersionReq.html#method.to_faithful_string
+/// [`Predicate::to_faithful_string`]: ./struct.Predicate.html#method.to_faithful_string
+enum Faithful<'a> {
+    Req(&'a VersionReq),
+    Predicate(&'a Predicate),
+}
+
+impl<'a> fmt::Display for Faithful<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let predicate_fmt = |predicate: &Predicate, f: &mut fmt::Formatter| {
+            let wildcard = predicate.wildcard().unwrap_or(WildcardShape::Star);
+            predicate.fmt_with_wildcard(f, wildcard.as_str())
+        };
+
+        match *self {
+            Faithful::Req(req) => req.fmt_with(f, predicate_fmt),
+            Faithful::Predicate(predicate) => predicate_fmt(predicate, f),
+        }
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with_wildcard(f, "*")
+    }
+}
+
+impl Predicate {
+    /// Write this predicate like `Display`, using the given wildcard character.
+    fn fmt_with_wildcard(&self, f: &mut fmt::Formatter, wildcard: &str) -> fmt::Result {
+        match self.op {
+            Op::Wildcard(WildcardVersion::Major) => return write!(f, "{}", wildcard),
+            Op::Wildcard(WildcardVersion::Minor) => {
+                return write!(f, "{}.{}", self.major, wildcard);
+            }
+            Op::Wildcard(WildcardVersion::Patch) => {
+                return match self.minor {
+                    Some(minor) => write!(f, "{}.{}.{}", self.major, minor, wildcard),
+                    None => write!(f, "{}.{}", self.major, wildcard),
+                };
+            }
+            ref op => write!(f, "{}{}", op, self.major)?,
+        }
+
+        if let Some(minor) = self.minor {
+            write!(f, ".{}", minor)?;
+
+            if let Some(patch) = self.patch {
+                write!(f, ".{}", patch)?;
+            }
+        }
+
+        if !self.pre.is_empty() {
+            let strs: Vec<_> = self.pre.iter().map(ToString::to_string).collect();
+            write!(f, "-{}", strs.join("."))?;
+        }
+
+        if !self.build.is_empty() {
+            let strs: Vec<_> = self.build.iter().map(ToString::to_string).collect();
+            write!(f, "+{}", strs.join("."))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use range;
+    use version::Identifier;
+
+    #[test]
+    fn test_parsing_wildcards() {
+        assert_eq!(
+            Op::Wildcard(WildcardVersion::Patch),
+            range::parse("1.0.*").unwrap().predicates[0].op
+        );
+        assert_eq!(
+            Op::Wildcard(WildcardVersion::Minor),
+            range::parse("1.*.*").unwrap().predicates[0].op
+        );
+        assert_eq!(
+            Op::Wildcard(WildcardVersion::Minor),
+            parse("1.*.0").unwrap().predicates[0].op
+        );
+    }
+
+    #[test]
+    fn test_parsing_default() {
+        let r = range::parse("1.0.0").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Compatible,
+                major: 1,
+                minor: Some(0),
+                patch: Some(0),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    fn test_parsing_exact_01() {
+        let r = range::parse("=1.0.0").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Ex,
+                major: 1,
+                minor: Some(0),
+                patch: Some(0),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
     }
 
     #[test]
@@ -447,604 +3680,2549 @@ mod tests {
                 minor: Some(9),
                 patch: Some(0),
                 pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
             },
             r.predicates[0]
         );
     }
 
     #[test]
-    fn test_parsing_exact_03() {
-        let r = range::parse("=0.1.0-beta2.a").unwrap();
+    fn test_parsing_exact_03() {
+        let r = range::parse("=0.1.0-beta2.a").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Ex,
+                major: 0,
+                minor: Some(1),
+                patch: Some(0),
+                pre: vec![
+                    Identifier::AlphaNumeric(String::from("beta2")),
+                    Identifier::AlphaNumeric(String::from("a")),
+                ],
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    pub fn test_parsing_greater_than() {
+        let r = range::parse("> 1.0.0").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Gt,
+                major: 1,
+                minor: Some(0),
+                patch: Some(0),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    pub fn test_parsing_greater_than_01() {
+        let r = range::parse(">= 1.0.0").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::GtEq,
+                major: 1,
+                minor: Some(0),
+                patch: Some(0),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    pub fn test_parsing_greater_than_02() {
+        let r = range::parse(">= 2.1.0-alpha2").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::GtEq,
+                major: 2,
+                minor: Some(1),
+                patch: Some(0),
+                pre: vec![Identifier::AlphaNumeric(String::from("alpha2"))],
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    pub fn test_parsing_less_than() {
+        let r = range::parse("< 1.0.0").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Lt,
+                major: 1,
+                minor: Some(0),
+                patch: Some(0),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    pub fn test_parsing_less_than_eq() {
+        let r = range::parse("<= 2.1.0-alpha2").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::LtEq,
+                major: 2,
+                minor: Some(1),
+                patch: Some(0),
+                pre: vec![Identifier::AlphaNumeric(String::from("alpha2"))],
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    pub fn test_parsing_tilde() {
+        let r = range::parse("~1").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Tilde,
+                major: 1,
+                minor: None,
+                patch: None,
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    pub fn test_parsing_compatible() {
+        let r = range::parse("^0").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Compatible,
+                major: 0,
+                minor: None,
+                patch: None,
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    fn test_parsing_blank() {
+        let r = range::parse("").unwrap();
+        assert!(r.predicates.is_empty());
+    }
+
+    #[test]
+    fn test_parsing_wildcard() {
+        let r = range::parse("*").unwrap();
+        assert!(r.predicates.is_empty());
+    }
+
+    #[test]
+    fn test_uppercase_prereleases() {
+        assert_eq!(
+            vec![Identifier::AlphaNumeric("Foo".to_string())],
+            range::parse("0-Foo").unwrap().predicates[0].pre
+        );
+
+        assert_eq!(
+            vec![Identifier::AlphaNumeric("X".to_string())],
+            range::parse("0-X").unwrap().predicates[0].pre
+        );
+    }
+
+    #[test]
+    fn test_strict_prereleases() {
+        use lexer::Token;
+
+        assert_eq!(
+            Err(parser::Error::UnexpectedToken(Token::Hyphen, 1)),
+            range::parse_strict("0-Foo")
+        );
+        assert_eq!(
+            Err(parser::Error::UnexpectedToken(Token::Hyphen, 5)),
+            range::parse_strict(">=1.2-rc")
+        );
+        assert_eq!(
+            Err(parser::Error::UnexpectedToken(Token::Plus, 10)),
+            range::parse_strict("^1.0.0, ~1+build")
+        );
+        assert!(range::parse("0-Foo").is_ok());
+        assert_eq!(range::parse("0.0.0-Foo"), range::parse_strict("0.0.0-Foo"));
+        assert_eq!(range::parse("^1.2, <2.0.0-0"), range::parse_strict("^1.2, <2.0.0-0"));
+    }
+
+    #[test]
+    fn test_empty_prerelease() {
+        assert!(range::parse("0-").is_err());
+    }
+
+    #[test]
+    fn test_parsing_x() {
+        let r = range::parse("x").unwrap();
+        assert!(r.predicates.is_empty());
+    }
+
+    #[test]
+    fn test_parsing_capital_x() {
+        let r = range::parse("X").unwrap();
+        assert!(r.predicates.is_empty());
+    }
+
+    #[test]
+    fn test_parsing_wildcard_star_star() {
+        let r = range::parse("1.*.*").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Wildcard(WildcardVersion::Minor),
+                major: 1,
+                minor: None,
+                patch: None,
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    fn test_parsing_minor_wildcard_star() {
+        let r = range::parse("1.*").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Wildcard(WildcardVersion::Minor),
+                major: 1,
+                minor: None,
+                patch: None,
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    fn test_parsing_minor_wildcard_star_patch() {
+        let r = range::parse("1.*.0").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Wildcard(WildcardVersion::Minor),
+                major: 1,
+                minor: None,
+                patch: Some(0),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    fn test_parsing_minor_wildcard_x() {
+        let r = range::parse("1.x").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Wildcard(WildcardVersion::Minor),
+                major: 1,
+                minor: None,
+                patch: None,
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    fn test_parsing_minor_wildcard_capital_x() {
+        let r = range::parse("1.X").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Wildcard(WildcardVersion::Minor),
+                major: 1,
+                minor: None,
+                patch: None,
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    fn test_parsing_patch_wildcard_star() {
+        let r = range::parse("1.2.*").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Wildcard(WildcardVersion::Patch),
+                major: 1,
+                minor: Some(2),
+                patch: None,
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    fn test_parsing_patch_wildcard_x() {
+        let r = range::parse("1.2.x").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Wildcard(WildcardVersion::Patch),
+                major: 1,
+                minor: Some(2),
+                patch: None,
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    fn test_parsing_patch_wildcard_capital_x() {
+        let r = range::parse("1.2.X").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Wildcard(WildcardVersion::Patch),
+                major: 1,
+                minor: Some(2),
+                patch: None,
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+    }
+
+    #[test]
+    pub fn test_multiple_01() {
+        let r = range::parse("> 0.0.9, <= 2.5.3").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Gt,
+                major: 0,
+                minor: Some(0),
+                patch: Some(9),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+
+        assert_eq!(
+            Predicate {
+                op: Op::LtEq,
+                major: 2,
+                minor: Some(5),
+                patch: Some(3),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[1]
+        );
+    }
+
+    #[test]
+    pub fn test_multiple_02() {
+        let r = range::parse("0.3.0, 0.4.0").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Compatible,
+                major: 0,
+                minor: Some(3),
+                patch: Some(0),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+
+        assert_eq!(
+            Predicate {
+                op: Op::Compatible,
+                major: 0,
+                minor: Some(4),
+                patch: Some(0),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[1]
+        );
+    }
+
+    #[test]
+    pub fn test_multiple_whitespace_around_comma() {
+        let canonical = range::parse("1.0, 2.0").unwrap();
+
+        for input in ["1.0 , 2.0", "1.0 ,2.0", "1.0,2.0", "1.0,  2.0", "1.0\t,\t2.0"].iter() {
+            let r = range::parse(input).unwrap();
+
+            assert_eq!(canonical, r);
+            assert_eq!(2, r.predicates.len());
+            assert_eq!("2.0", r.predicates[1].raw);
+        }
+    }
+
+    #[test]
+    pub fn test_multiple_03() {
+        let r = range::parse("<= 0.2.0, >= 0.5.0").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::LtEq,
+                major: 0,
+                minor: Some(2),
+                patch: Some(0),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+
+        assert_eq!(
+            Predicate {
+                op: Op::GtEq,
+                major: 0,
+                minor: Some(5),
+                patch: Some(0),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[1]
+        );
+    }
+
+    #[test]
+    pub fn test_multiple_04() {
+        let r = range::parse("0.1.0, 0.1.4, 0.1.6").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::Compatible,
+                major: 0,
+                minor: Some(1),
+                patch: Some(0),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+
+        assert_eq!(
+            Predicate {
+                op: Op::Compatible,
+                major: 0,
+                minor: Some(1),
+                patch: Some(4),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[1]
+        );
+
+        assert_eq!(
+            Predicate {
+                op: Op::Compatible,
+                major: 0,
+                minor: Some(1),
+                patch: Some(6),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[2]
+        );
+    }
+
+    #[test]
+    pub fn test_multiple_05() {
+        let r = range::parse(">=0.5.1-alpha3, <0.6").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::GtEq,
+                major: 0,
+                minor: Some(5),
+                patch: Some(1),
+                pre: vec![Identifier::AlphaNumeric(String::from("alpha3"))],
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+
+        assert_eq!(
+            Predicate {
+                op: Op::Lt,
+                major: 0,
+                minor: Some(6),
+                patch: None,
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[1]
+        );
+    }
+
+    #[test]
+    pub fn test_multiple_06() {
+        let r = range::parse("<= 0.2.0 >= 0.5.0").unwrap();
+
+        assert_eq!(
+            Predicate {
+                op: Op::LtEq,
+                major: 0,
+                minor: Some(2),
+                patch: Some(0),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[0]
+        );
+
+        assert_eq!(
+            Predicate {
+                op: Op::GtEq,
+                major: 0,
+                minor: Some(5),
+                patch: Some(0),
+                pre: Vec::new(),
+                build: Vec::new(),
+                raw: String::new(),
+            },
+            r.predicates[1]
+        );
+    }
+
+    #[test]
+    fn test_parse_build_metadata_with_predicate() {
+        assert_eq!(
+            range::parse("^1.2.3+meta").unwrap().predicates[0].op,
+            Op::Compatible
+        );
+        assert_eq!(
+            range::parse("~1.2.3+meta").unwrap().predicates[0].op,
+            Op::Tilde
+        );
+        assert_eq!(
+            range::parse("=1.2.3+meta").unwrap().predicates[0].op,
+            Op::Ex
+        );
+        assert_eq!(
+            range::parse("<=1.2.3+meta").unwrap().predicates[0].op,
+            Op::LtEq
+        );
+        assert_eq!(
+            range::parse(">=1.2.3+meta").unwrap().predicates[0].op,
+            Op::GtEq
+        );
+        assert_eq!(
+            range::parse("<1.2.3+meta").unwrap().predicates[0].op,
+            Op::Lt
+        );
+        assert_eq!(
+            range::parse(">1.2.3+meta").unwrap().predicates[0].op,
+            Op::Gt
+        );
+    }
+
+    #[test]
+    fn test_parse_build_metadata_partial() {
+        let meta = vec![Identifier::AlphaNumeric("meta".to_string())];
+        let alpha = vec![Identifier::AlphaNumeric("alpha".to_string())];
+
+        let cases = [
+            ("^1+meta", "^1+meta", Predicate::builder(Op::Compatible, 1)),
+            ("~1.2+meta", "~1.2+meta", Predicate::builder(Op::Tilde, 1).minor(2)),
+            ("1+meta", "^1+meta", Predicate::builder(Op::Compatible, 1)),
+            (">=1.2+meta", ">=1.2+meta", Predicate::builder(Op::GtEq, 1).minor(2)),
+            ("=1-alpha+meta", "=1-alpha+meta", Predicate::builder(Op::Ex, 1).pre(alpha)),
+        ];
+
+        for &(input, display, ref builder) in cases.iter() {
+            let predicate = range::parse_predicate(input).unwrap().unwrap();
+
+            assert_eq!(builder.clone().build(), predicate, "{}", input);
+            assert_eq!(meta, predicate.build, "{}", input);
+            assert_eq!(display, predicate.to_string());
+        }
+
+        let with_meta = range::parse("^1+meta").unwrap();
+        let without = range::parse("^1").unwrap();
+        for version in &["1.0.0", "1.9.9", "2.0.0", "0.9.0"] {
+            assert_eq!(without.matches(&v(version)), with_meta.matches(&v(version)));
+        }
+    }
+
+    #[test]
+    pub fn test_parse_errors() {
+        assert!(range::parse("\0").is_err());
+        assert!(range::parse(">= >= 0.0.2").is_err());
+        assert!(range::parse(">== 0.0.2").is_err());
+        assert!(range::parse("a.0.0").is_err());
+        assert!(range::parse("1.0.0-").is_err());
+        assert!(range::parse(">=").is_err());
+        assert!(range::parse("> 0.1.0,").is_err());
+        assert!(range::parse("> 0.3.0, ,").is_err());
+        assert!(range::parse("> 0. 1").is_err());
+    }
+
+    #[test]
+    pub fn test_large_major_version() {
+        assert!(range::parse("18446744073709551617.0.0").is_err());
+    }
+
+    #[test]
+    pub fn test_large_minor_version() {
+        assert!(range::parse("0.18446744073709551617.0").is_err());
+    }
+
+    #[test]
+    pub fn test_large_patch_version() {
+        assert!(range::parse("0.0.18446744073709551617").is_err());
+    }
+
+    #[test]
+    pub fn test_op_partialord_lt() {
+        let expect_less = Op::Ex;
+        let other = Op::Gt;
+        assert!(expect_less.lt(&other));
+    }
+
+    #[test]
+    pub fn test_op_partialord_le() {
+        let strictly_lt = Op::Ex;
+        let other = Op::Lt;
+        assert!(strictly_lt.le(&other));
+        assert!(other.le(&other));
+    }
+
+    #[test]
+    pub fn test_op_partialord_gt() {
+        let expect_gt = Op::Compatible;
+        let other = Op::GtEq;
+        assert!(expect_gt.gt(&other));
+    }
+
+    #[test]
+    pub fn test_op_partialord_ge() {
+        let strictly_gt = Op::Compatible;
+        let other = Op::Tilde;
+        assert!(strictly_gt.ge(&other));
+        assert!(other.ge(&other));
+    }
+
+    #[test]
+    pub fn test_wildcard_partialord_lt() {
+        let expect_less = WildcardVersion::Minor;
+        let other = WildcardVersion::Patch;
+        assert!(expect_less.lt(&other));
+    }
+
+
+    #[test]
+    pub fn test_wildcard_partialord_le() {
+        let strictly_lt = WildcardVersion::Minor;
+        let other = WildcardVersion::Patch;
+        assert!(strictly_lt.le(&other));
+        assert!(other.le(&other));
+    }
+
+    #[test]
+    pub fn test_wildcard_partialord_gt() {
+        let expect_greater = WildcardVersion::Patch;
+        let other = WildcardVersion::Minor;
+        assert!(expect_greater.gt(&other));
+    }
+
+    #[test]
+    pub fn test_wildcard_partialord_ge() {
+        let strictly_gt = WildcardVersion::Patch;
+        let other = WildcardVersion::Minor;
+        assert!(strictly_gt.ge(&other));
+        assert!(other.ge(&other));
+    }
+
+    fn matches(predicate: &str, version: &str) -> bool {
+        range::parse_predicate(predicate)
+            .unwrap()
+            .expect("non-empty")
+            .matches(&::version::parse(version).unwrap())
+    }
+
+    #[test]
+    fn test_matches_compatible_zero_major() {
+        assert!(matches("^0.1", "0.1.0"));
+        assert!(matches("^0.1", "0.1.9"));
+        assert!(!matches("^0.1", "0.2.0"));
+        assert!(matches("^0.1.2", "0.1.5"));
+        assert!(!matches("^0.1.2", "0.1.1"));
+        assert!(!matches("^0.1.2", "0.2.0"));
+        assert!(matches("^0", "0.9.9"));
+        assert!(!matches("^0", "1.0.0"));
+    }
+
+    #[test]
+    fn test_matches_compatible() {
+        assert!(matches("^1.2.3", "1.2.3"));
+        assert!(matches("^1.2.3", "1.9.0"));
+        assert!(!matches("^1.2.3", "1.2.2"));
+        assert!(!matches("^1.2.3", "2.0.0"));
+        assert!(matches("1.2", "1.5.0"));
+    }
+
+    #[test]
+    fn test_matches_tilde() {
+        assert!(matches("~1.2", "1.2.0"));
+        assert!(matches("~1.2", "1.2.9"));
+        assert!(!matches("~1.2", "1.3.0"));
+        assert!(!matches("~1.2", "1.1.9"));
+        assert!(matches("~1.2.3", "1.2.4"));
+        assert!(!matches("~1.2.3", "1.2.2"));
+        assert!(matches("~1", "1.9.0"));
+        assert!(!matches("~1", "2.0.0"));
+    }
+
+    #[test]
+    fn test_matches_exact_prerelease() {
+        assert!(matches("=1.2.3", "1.2.3"));
+        assert!(!matches("=1.2.3", "1.2.4"));
+        assert!(!matches("=1.2.3", "1.2.3-alpha"));
+        assert!(matches("=1.2.3-alpha", "1.2.3-alpha"));
+        assert!(!matches("=1.2.3-alpha", "1.2.3-beta"));
+        assert!(!matches("=1.2.3-alpha", "1.2.3"));
+    }
+
+    #[test]
+    fn test_matches_prerelease_rules() {
+        assert!(matches(">=1.2.3-alpha", "1.2.3-beta"));
+        assert!(matches(">=1.2.3-alpha", "1.2.3"));
+        assert!(!matches(">=1.2.3-alpha", "1.2.4-beta"));
+        assert!(!matches(">=1.0.0", "1.2.0-beta"));
+        assert!(matches("^1.2.3-alpha.1", "1.2.3-alpha.2"));
+        assert!(!matches("^1.2.3-alpha.2", "1.2.3-alpha.1"));
+    }
+
+    #[test]
+    fn test_matches_comparisons() {
+        assert!(matches(">1.0.0", "1.0.1"));
+        assert!(!matches(">1.0.0", "1.0.0"));
+        assert!(matches(">=1.0.0", "1.0.0"));
+        assert!(matches("<1.0.0", "0.9.9"));
+        assert!(!matches("<1.0.0", "1.0.0"));
+        assert!(matches("<=1.0.0", "1.0.0"));
+        assert!(!matches("<=1.0.0", "1.0.1"));
+        assert!(!matches("<1.2", "1.2.5"));
+        assert!(matches("<=1.2", "1.2.5"));
+        assert!(matches(">1.2", "1.3.0"));
+        assert!(!matches(">1.2", "1.2.5"));
+    }
+
+    #[test]
+    fn test_matches_wildcard() {
+        assert!(matches("1.*", "1.0.0"));
+        assert!(matches("1.*", "1.9.9"));
+        assert!(!matches("1.*", "2.0.0"));
+        assert!(matches("1.*.*", "1.9.9"));
+        assert!(!matches("1.*.*", "2.0.0"));
+        assert!(matches("1.2.*", "1.2.9"));
+        assert!(!matches("1.2.*", "1.3.0"));
+        assert!(!matches("1.2.*", "1.2.0-alpha"));
+    }
+
+    fn req_matches(range: &str, version: &str) -> bool {
+        range::parse(range).unwrap().matches(&::version::parse(version).unwrap())
+    }
+
+    #[test]
+    fn test_req_matches_multiple() {
+        assert!(req_matches(">= 1.2.0, < 2.0.0", "1.5.0"));
+        assert!(req_matches(">= 1.2.0, < 2.0.0", "1.2.0"));
+        assert!(!req_matches(">= 1.2.0, < 2.0.0", "2.0.0"));
+        assert!(!req_matches(">= 1.2.0, < 2.0.0", "1.1.9"));
+        assert!(!req_matches(">= 1.2.0, < 2.0.0", "1.5.0-alpha"));
+    }
+
+    #[test]
+    fn test_req_matches_any() {
+        assert!(req_matches("*", "0.0.0"));
+        assert!(req_matches("*", "42.1.7"));
+        assert!(req_matches("", "1.0.0"));
+        assert!(!req_matches("*", "1.0.0-alpha"));
+    }
+
+    #[test]
+    fn test_req_matches_prerelease_in_any_predicate() {
+        assert!(req_matches(">= 1.2.0-alpha, < 2.0.0", "1.2.0-beta"));
+        assert!(!req_matches(">= 1.2.0-alpha, < 2.0.0", "1.5.0-beta"));
+        assert!(req_matches(">= 1.0.0, < 2.0.0-rc.2", "2.0.0-rc.1"));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let ranges = [
+            "~1",
+            "~1.2",
+            "~1.2.3",
+            "^0",
+            "^0.2",
+            "^1.2.3",
+            "=1.2.3",
+            "=0.1.0-beta2.a",
+            "=1.2.3+meta",
+            "^1.2.3-alpha.1+build.5",
+            ">=1.0.0, <2.0.0",
+            ">0.0.9, <=2.5.3",
+            "1.*",
+            "1.2.*",
+            "*",
+            "^1.2 || ~2.3",
+            ">=1.0.0, <1.5.0 || >=2.0.0",
+        ];
+
+        for r in ranges.iter() {
+            let parsed = range::parse(r).unwrap();
+            let displayed = parsed.to_string();
+
+            assert_eq!(*r, displayed);
+            assert_eq!(parsed, range::parse(&displayed).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_display_default_op() {
+        assert_eq!("^1.2.3", range::parse("1.2.3").unwrap().to_string());
+        assert_eq!("1.*", range::parse("1.x").unwrap().to_string());
+        assert_eq!("1.2.*", range::parse("1.2.X").unwrap().to_string());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        use serde_json;
+
+        let ranges = ["^1.2.3", "~1", ">=1.0.0, <2.0.0", "1.2.*", "*"];
+
+        for r in ranges.iter() {
+            let parsed = range::parse(r).unwrap();
+            let json = serde_json::to_string(&parsed).unwrap();
+
+            assert_eq!(format!("\"{}\"", r), json);
+            assert_eq!(parsed, serde_json::from_str::<VersionReq>(&json).unwrap());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_predicate() {
+        use serde_json;
+
+        let predicate = range::parse_predicate(">=1.2.0-alpha").unwrap().unwrap();
+        let json = serde_json::to_string(&predicate).unwrap();
+
+        assert_eq!(predicate, serde_json::from_str::<Predicate>(&json).unwrap());
+        assert!(serde_json::from_str::<VersionReq>("\">== 1.0\"").is_err());
+
+        // `build` and `raw` can be left out.
+        let json = r#"{"op":"Ex","major":1,"minor":2,"patch":3,"pre":[]}"#;
+        let predicate = serde_json::from_str::<Predicate>(json).unwrap();
+        assert!(predicate.build.is_empty() && predicate.raw.is_empty());
+    }
+
+    #[cfg(feature = "semver-compat")]
+    #[test]
+    fn test_semver_round_trip() {
+        use semver;
+
+        let ranges = ["^1.2.3", "~1.2", ">=1.0.0, <2.0.0", "1.2.*", "1.*", "=1.2.3-rc.1", "*"];
+
+        for r in ranges.iter() {
+            let parsed = range::parse(r).unwrap();
+            let converted = semver::VersionReq::try_from(&parsed).unwrap();
+
+            assert_eq!(semver::VersionReq::parse(r).unwrap(), converted);
+            assert_eq!(*r, converted.to_string());
+            assert_eq!(parsed, VersionReq::try_from(&converted).unwrap());
+        }
+    }
+
+    #[cfg(feature = "semver-compat")]
+    #[test]
+    fn test_semver_unsupported() {
+        use semver;
+
+        let or_groups = range::parse("^1 || ^2").unwrap();
+        let pessimistic = range::parse("~> 1.2").unwrap();
+        let hyphenated = semver::VersionReq::parse("^1.0.0-alpha-1").unwrap();
+
+        assert!(semver::VersionReq::try_from(&or_groups).is_err());
+        assert!(semver::VersionReq::try_from(&pessimistic).is_err());
+        assert!(VersionReq::try_from(&hyphenated).is_err());
+    }
+
+    #[test]
+    fn test_contains_prerelease_predicate() {
+        assert!(range::parse(">=1.0.0-beta").unwrap().contains_prerelease_predicate());
+        assert!(range::parse(">=1.0.0, <2.0.0-0").unwrap().contains_prerelease_predicate());
+        assert!(range::parse("^1 || ~2.1.0-rc").unwrap().contains_prerelease_predicate());
+        assert!(!range::parse(">=1.0.0").unwrap().contains_prerelease_predicate());
+        assert!(!range::parse("=1.0.0+build").unwrap().contains_prerelease_predicate());
+        assert!(!range::parse("*").unwrap().contains_prerelease_predicate());
+    }
+
+    #[test]
+    fn test_matches_cached() {
+        let reqs = [
+            range::parse("^1.2").unwrap(),
+            range::parse(">=1.0.0-rc.1, <1.5.0 || ~2").unwrap(),
+            range::parse("*").unwrap(),
+        ];
+        let versions = ["1.0.0-rc.2", "1.2.0", "1.5.0", "2.0.9", "3.0.0", "1.2.0+build"];
+        let mut cache = MatchCache::new();
+
+        for round in 0..2 {
+            for r in reqs.iter() {
+                for v in versions.iter() {
+                    let v = version::parse(v).unwrap();
+
+                    assert_eq!(r.matches(&v), r.matches_cached(&v, &mut cache));
+                }
+            }
+
+            assert_eq!(3 * 6, cache.len());
+            assert_eq!(round * 3 * 6, cache.hits());
+        }
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(0, cache.hits());
+    }
+
+    #[test]
+    fn test_parsing_or_groups() {
+        let r = range::parse("1.2 || 2.3").unwrap();
+
+        assert_eq!(
+            vec![
+                Predicate {
+                    op: Op::Compatible,
+                    major: 1,
+                    minor: Some(2),
+                    patch: None,
+                    pre: Vec::new(),
+                    build: Vec::new(),
+                    raw: String::new(),
+                },
+            ],
+            r.predicates
+        );
+
+        assert_eq!(
+            vec![
+                vec![
+                    Predicate {
+                        op: Op::Compatible,
+                        major: 2,
+                        minor: Some(3),
+                        patch: None,
+                        pre: Vec::new(),
+                        build: Vec::new(),
+                        raw: String::new(),
+                    },
+                ],
+            ],
+            r.or_groups
+        );
+    }
+
+    #[test]
+    fn test_parsing_or_groups_spaces() {
+        let r = range::parse(">= 1.2,  < 1.5   ||   >=  2.3.0").unwrap();
+
+        assert_eq!(2, r.predicates.len());
+        assert_eq!(1, r.or_groups.len());
+        assert_eq!(Op::GtEq, r.or_groups[0][0].op);
+        assert_eq!(range::parse(">=1.2, <1.5||>=2.3.0").unwrap(), r);
+    }
+
+    #[test]
+    fn test_parsing_without_or_groups() {
+        assert!(range::parse(">= 1.0.0, < 2.0.0").unwrap().or_groups.is_empty());
+    }
+
+    #[test]
+    fn test_parsing_empty_or_group() {
+        assert_eq!(Err(parser::Error::EmptyRange), range::parse("1.2 ||"));
+        assert_eq!(Err(parser::Error::EmptyRange), range::parse("1.2 || || 2.3"));
+        assert_eq!(Err(parser::Error::EmptyRange), range::parse("|| 1.2"));
+        assert_eq!(Err(parser::Error::EmptyPredicate(3)), range::parse("1.2, || 2.3"));
+    }
+
+    #[test]
+    fn test_req_matches_or_groups() {
+        assert!(req_matches("1.2 || 2.3", "1.2.0"));
+        assert!(req_matches("1.2 || 2.3", "2.5.0"));
+        assert!(!req_matches("1.2 || 2.3", "2.2.0"));
+        assert!(!req_matches("1.2 || 2.3", "3.0.0"));
+        assert!(req_matches("<1.0.0 || >=2.0.0, <3.0.0", "2.1.0"));
+        assert!(!req_matches("<1.0.0 || >=2.0.0, <3.0.0", "1.5.0"));
+    }
+
+    #[test]
+    fn test_parse_build_metadata_retained() {
+        let r = range::parse("=1.2.3+meta").unwrap();
+
+        assert_eq!(
+            vec![Identifier::AlphaNumeric(String::from("meta"))],
+            r.predicates[0].build
+        );
+
+        let r = range::parse(">=1.2.3-alpha+build.5").unwrap();
+
+        assert_eq!(
+            vec![Identifier::AlphaNumeric(String::from("alpha"))],
+            r.predicates[0].pre
+        );
+        assert_eq!(
+            vec![
+                Identifier::AlphaNumeric(String::from("build")),
+                Identifier::Numeric(5),
+            ],
+            r.predicates[0].build
+        );
+    }
+
+    #[test]
+    fn test_predicate_build_ignored_in_comparisons() {
+        use std::cmp::Ordering;
+        use std::collections::HashSet;
+
+        let a = range::parse("=1.2.3+meta").unwrap().predicates.remove(0);
+        let b = range::parse("=1.2.3+other").unwrap().predicates.remove(0);
+
+        assert_eq!(a, b);
+        assert_eq!(Ordering::Equal, a.cmp(&b));
+
+        let set: HashSet<_> = vec![a, b].into_iter().collect();
+        assert_eq!(1, set.len());
+    }
+
+    #[test]
+    fn test_from_str() {
+        let r: VersionReq = ">= 1.0.0, < 2.0.0".parse().unwrap();
+
+        assert_eq!(range::parse(">= 1.0.0, < 2.0.0").unwrap(), r);
+        assert!(">== 1.0.0".parse::<VersionReq>().is_err());
+        assert!("> 0.1.0,".parse::<VersionReq>().is_err());
+    }
+
+    #[test]
+    pub fn test_op_as_str_round_trip() {
+        let ops = [
+            Op::Ex,
+            Op::Gt,
+            Op::GtEq,
+            Op::Lt,
+            Op::LtEq,
+            Op::Tilde,
+            Op::Compatible,
+            Op::Pessimistic,
+        ];
+
+        for op in ops.iter() {
+            assert_eq!(Ok(op.clone()), Op::from_str(op.as_str()));
+            assert_eq!(op.as_str(), op.to_string());
+        }
+    }
+
+    #[test]
+    pub fn test_op_wildcard_as_str() {
+        assert_eq!("*", Op::Wildcard(WildcardVersion::Minor).as_str());
+        assert_eq!("*", Op::Wildcard(WildcardVersion::Patch).to_string());
+        assert!(Op::from_str("*").is_err());
+    }
+
+    #[test]
+    fn test_any() {
+        let r = VersionReq::any();
+
+        assert!(r.predicates.is_empty());
+        assert_eq!(range::parse("*").unwrap(), r);
+
+        for v in ["0.0.0", "1.2.3", "99.0.1"].iter() {
+            assert!(r.matches(&::version::parse(v).unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_exact() {
+        let v = ::version::parse("1.2.3").unwrap();
+        let r = VersionReq::exact(&v);
+
+        assert_eq!(range::parse("=1.2.3").unwrap(), r);
+        assert!(r.matches(&v));
+        assert!(!r.matches(&::version::parse("1.2.4").unwrap()));
+        assert!(!r.matches(&::version::parse("1.2.2").unwrap()));
+    }
+
+    #[test]
+    fn test_exact_prerelease() {
+        let v = ::version::parse("1.2.3-beta.2").unwrap();
+        let r = VersionReq::exact(&v);
+
+        assert!(r.matches(&v));
+        assert!(!r.matches(&::version::parse("1.2.3-beta.3").unwrap()));
+        assert!(!r.matches(&::version::parse("1.2.3").unwrap()));
+    }
+
+    #[test]
+    fn test_predicate_is_prerelease() {
+        let p = range::parse_predicate(">=1.2.3-alpha1").unwrap().unwrap();
+        assert!(p.is_prerelease());
+
+        let p = range::parse_predicate("^1.2.3+build").unwrap().unwrap();
+        assert!(!p.is_prerelease());
+    }
+
+    fn comparators(predicate: &str) -> Vec<String> {
+        range::parse_predicate(predicate)
+            .unwrap()
+            .expect("non-empty")
+            .to_comparators()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_to_comparators_compatible() {
+        assert_eq!(vec![">=1.2.3", "<2.0.0"], comparators("^1.2.3"));
+        assert_eq!(vec![">=1.2.0", "<2.0.0"], comparators("^1.2"));
+        assert_eq!(vec![">=1.0.0", "<2.0.0"], comparators("^1"));
+        assert_eq!(vec![">=1.2.3", "<2.0.0"], comparators("1.2.3"));
+        assert_eq!(vec![">=0.2.3", "<0.3.0"], comparators("^0.2.3"));
+        assert_eq!(vec![">=0.2.0", "<0.3.0"], comparators("^0.2"));
+        assert_eq!(vec![">=0.0.3", "<0.0.4"], comparators("^0.0.3"));
+        assert_eq!(vec![">=0.0.0", "<0.1.0"], comparators("^0.0"));
+        assert_eq!(vec![">=0.0.0", "<1.0.0"], comparators("^0"));
+        assert_eq!(vec![">=1.2.3-alpha", "<2.0.0"], comparators("^1.2.3-alpha"));
+    }
+
+    #[test]
+    fn test_to_comparators_tilde() {
+        assert_eq!(vec![">=1.2.3", "<1.3.0"], comparators("~1.2.3"));
+        assert_eq!(vec![">=1.2.0", "<1.3.0"], comparators("~1.2"));
+        assert_eq!(vec![">=1.0.0", "<2.0.0"], comparators("~1"));
+    }
+
+    #[test]
+    fn test_to_comparators_wildcard() {
+        assert_eq!(vec![">=1.2.0", "<1.3.0"], comparators("1.2.*"));
+        assert_eq!(vec![">=1.0.0", "<2.0.0"], comparators("1.*"));
+        assert_eq!(vec![">=1.0.0", "<2.0.0"], comparators("1.*.*"));
+    }
+
+    #[test]
+    fn test_to_comparators_exact() {
+        assert_eq!(vec!["=1.2.3"], comparators("=1.2.3"));
+        assert_eq!(vec!["=1.2.3-beta"], comparators("=1.2.3-beta+build"));
+        assert_eq!(vec![">=1.2.0", "<1.3.0"], comparators("=1.2"));
+        assert_eq!(vec![">=1.0.0", "<2.0.0"], comparators("=1"));
+    }
+
+    #[test]
+    fn test_to_comparators_comparisons() {
+        assert_eq!(vec![">1.2.3"], comparators(">1.2.3"));
+        assert_eq!(vec![">=1.3.0"], comparators(">1.2"));
+        assert_eq!(vec![">=2.0.0"], comparators(">1"));
+        assert_eq!(vec![">=1.2.3"], comparators(">=1.2.3"));
+        assert_eq!(vec![">=1.2.0"], comparators(">=1.2"));
+        assert_eq!(vec!["<1.2.3"], comparators("<1.2.3"));
+        assert_eq!(vec!["<1.2.0"], comparators("<1.2"));
+        assert_eq!(vec!["<=1.2.3"], comparators("<=1.2.3"));
+        assert_eq!(vec!["<1.3.0"], comparators("<=1.2"));
+        assert_eq!(vec!["<2.0.0"], comparators("<=1"));
+    }
+
+    #[test]
+    fn test_to_comparators_overflow() {
+        assert_eq!(
+            vec![">=18446744073709551615.0.0"],
+            comparators("^18446744073709551615")
+        );
+        assert_eq!(
+            vec![">=1.18446744073709551615.0", "<2.0.0"],
+            comparators("~1.18446744073709551615")
+        );
+        assert!(comparators("<=18446744073709551615").is_empty());
+
+        let max = "18446744073709551615.18446744073709551615.18446744073709551615";
+        assert_eq!(vec![format!(">{}", max)], comparators(">18446744073709551615"));
+        assert_eq!(
+            vec![format!(">{}", max)],
+            comparators(">18446744073709551615.18446744073709551615")
+        );
+
+        let r = range::parse(">18446744073709551615").unwrap();
+        let greatest = Version::new(u64::MAX, u64::MAX, u64::MAX);
+        assert!(!r.matches(&greatest));
+        assert!(!r.normalize().matches(&greatest));
+        assert!(!r.normalize().is_any());
+    }
+
+    #[test]
+    fn test_normalize() {
+        let r = range::parse("^1.2.3, ~1.4 || 2.*").unwrap().normalize();
+
+        assert_eq!(">=1.2.3, <2.0.0, >=1.4.0, <1.5.0 || >=2.0.0, <3.0.0", r.to_string());
+        assert_eq!(range::parse("*").unwrap(), range::parse("*").unwrap().normalize());
+    }
+
+    #[test]
+    fn test_normalize_matches_same_versions() {
+        let ranges = ["^1.2.3", "^0.2.3", "^0.0.3", "~1.2", "~1", "1.2.*", "=1.2", "<=1.2", ">1.2"];
+        let versions = [
+            "0.0.2", "0.0.3", "0.0.4", "0.2.3", "0.2.9", "0.3.0", "1.1.9", "1.2.0", "1.2.3",
+            "1.2.9", "1.3.0", "1.9.9", "2.0.0",
+        ];
+
+        for r in ranges.iter() {
+            let parsed = range::parse(r).unwrap();
+            let normalized = parsed.normalize();
+
+            for v in versions.iter() {
+                let v = ::version::parse(v).unwrap();
+                assert_eq!(parsed.matches(&v), normalized.matches(&v), "{} {}", r, v);
+            }
+        }
+    }
+
+    fn intersect(a: &str, b: &str) -> Option<String> {
+        range::parse(a)
+            .unwrap()
+            .intersect(&range::parse(b).unwrap())
+            .map(|r| r.to_string())
+    }
+
+    #[test]
+    fn test_intersect_overlapping() {
+        assert_eq!(
+            Some(">=1.2.0, <2.0.0, >=1.4.0, <1.5.0".to_string()),
+            intersect("^1.2", "~1.4")
+        );
+        assert_eq!(Some(">=1.0.0, <1.5.0".to_string()), intersect(">=1.0.0", "<1.5.0"));
+        assert_eq!(Some("<1.5.0".to_string()), intersect("*", "<1.5.0"));
+        assert_eq!(Some("=1.2.3, >=1.0.0, <2.0.0".to_string()), intersect("=1.2.3", "^1"));
+        assert_eq!(Some(">=1.0.0, <=1.0.0".to_string()), intersect(">=1.0.0", "<=1.0.0"));
+    }
+
+    #[test]
+    fn test_intersect_disjoint() {
+        assert_eq!(None, intersect("<1.0.0", ">=2.0.0"));
+        assert_eq!(None, intersect("^1.2", "^2"));
+        assert_eq!(None, intersect("~0.1", "~0.2"));
+    }
+
+    #[test]
+    fn test_intersect_adjacent() {
+        assert_eq!(None, intersect("<1.0.0", ">=1.0.0"));
+        assert_eq!(None, intersect("<=1.0.0", ">1.0.0"));
+        assert_eq!(None, intersect("^1", "^2"));
+        assert_eq!(None, intersect("<1.0.0-alpha", ">1.0.0-alpha"));
+    }
+
+    #[test]
+    fn test_intersect_exact() {
+        assert_eq!(None, intersect("=1.0.0", "=1.0.1"));
+        assert_eq!(None, intersect("=1.0.0", "=1.0.0-alpha"));
+        assert_eq!(None, intersect("=1.0.0", "<1.0.0"));
+        assert_eq!(Some("=1.0.0, =1.0.0".to_string()), intersect("=1.0.0", "=1.0.0"));
+    }
+
+    #[test]
+    fn test_intersect_prerelease_order() {
+        assert_eq!(None, intersect(">=1.0.0", "<1.0.0-rc.1"));
+        assert_eq!(None, intersect(">=1.0.0-beta.11", "<1.0.0-beta.2"));
+        assert!(intersect(">=1.0.0-alpha", "<1.0.0").is_some());
+    }
+
+    #[test]
+    fn test_intersect_or_groups() {
+        assert_eq!(
+            Some(">=2.0.0, <3.0.0, >=2.1.0".to_string()),
+            intersect("^1 || ^2", ">=2.1.0")
+        );
+        assert_eq!(None, intersect("^1 || ^2", "^3 || ^4"));
+    }
+
+    #[test]
+    fn test_classification() {
+        let any = range::parse("*").unwrap();
+        assert!(any.is_any());
+        assert!(!any.is_exact());
+        assert!(!any.is_wildcard());
+
+        let exact = range::parse("=1.2.3").unwrap();
+        assert!(!exact.is_any());
+        assert!(exact.is_exact());
+        assert!(!exact.is_wildcard());
+
+        let wildcard = range::parse("1.2.*").unwrap();
+        assert!(!wildcard.is_any());
+        assert!(!wildcard.is_exact());
+        assert!(wildcard.is_wildcard());
+
+        let caret = range::parse("^1.2.3").unwrap();
+        assert!(!caret.is_any());
+        assert!(!caret.is_exact());
+        assert!(!caret.is_wildcard());
+
+        assert!(VersionReq::any().is_any());
+        assert!(VersionReq::exact(&Version::new(1, 2, 3)).is_exact());
+        assert!(!range::parse("=1.2.3, <2.0.0").unwrap().is_exact());
+    }
+
+    #[test]
+    fn test_compatible_zero_major_boundaries() {
+        // (range, versions matching, versions not matching)
+        let cases: &[(&str, &[&str], &[&str])] = &[
+            ("^0.0.3", &["0.0.3"], &["0.0.2", "0.0.4", "0.1.0", "1.0.0"]),
+            ("^0.2.3", &["0.2.3", "0.2.4", "0.2.99"], &["0.2.2", "0.3.0", "1.0.0"]),
+            ("^0", &["0.0.0", "0.0.1", "0.1.0", "0.99.99"], &["1.0.0", "1.0.1"]),
+            ("^0.0", &["0.0.0", "0.0.1", "0.0.99"], &["0.1.0", "1.0.0"]),
+            ("^0.2", &["0.2.0", "0.2.9"], &["0.1.9", "0.3.0"]),
+        ];
+
+        for &(r, matching, not_matching) in cases {
+            let parsed = range::parse(r).unwrap();
+            let normalized = parsed.normalize();
+
+            for v in matching {
+                let v = ::version::parse(v).unwrap();
+                assert!(parsed.matches(&v), "{} should match {}", r, v);
+                assert!(normalized.matches(&v), "{} should match {}", normalized, v);
+            }
+
+            for v in not_matching {
+                let v = ::version::parse(v).unwrap();
+                assert!(!parsed.matches(&v), "{} should not match {}", r, v);
+                assert!(!normalized.matches(&v), "{} should not match {}", normalized, v);
+            }
+        }
+    }
+
+    fn bounds(predicate: &str) -> (Bound<Version>, Bound<Version>) {
+        range::parse_predicate(predicate).unwrap().expect("non-empty").bounds()
+    }
+
+    fn v(version: &str) -> Version {
+        ::version::parse(version).unwrap()
+    }
+
+    #[test]
+    fn test_bounds() {
+        use core::ops::Bound::*;
+
+        assert_eq!((Included(v("1.2.3")), Included(v("1.2.3"))), bounds("=1.2.3"));
+        assert_eq!((Included(v("1.2.0")), Excluded(v("1.3.0"))), bounds("=1.2"));
+        assert_eq!((Excluded(v("1.0.0")), Unbounded), bounds(">1.0.0"));
+        assert_eq!((Included(v("1.1.0")), Unbounded), bounds(">1.0"));
+        assert_eq!((Included(v("1.0.0")), Unbounded), bounds(">=1.0.0"));
+        assert_eq!((Unbounded, Excluded(v("1.0.0"))), bounds("<1.0.0"));
+        assert_eq!((Unbounded, Included(v("1.0.0"))), bounds("<=1.0.0"));
+        assert_eq!((Included(v("1.2.3")), Excluded(v("1.3.0"))), bounds("~1.2.3"));
+        assert_eq!((Included(v("1.2.3")), Excluded(v("2.0.0"))), bounds("^1.2.3"));
+        assert_eq!((Included(v("0.0.3")), Excluded(v("0.0.4"))), bounds("^0.0.3"));
+        assert_eq!((Included(v("1.2.0")), Excluded(v("1.3.0"))), bounds("1.2.*"));
+        assert_eq!((Included(v("1.0.0")), Excluded(v("2.0.0"))), bounds("1.*"));
+        assert_eq!(
+            (Included(v("1.2.3-alpha")), Excluded(v("2.0.0"))),
+            bounds("^1.2.3-alpha")
+        );
+    }
+
+    #[test]
+    fn test_bounds_overflow() {
+        use core::ops::Bound::*;
+
+        let greatest = Version::new(u64::MAX, u64::MAX, u64::MAX);
+
+        assert_eq!(
+            (Included(Version::new(u64::MAX, 0, 0)), Unbounded),
+            bounds("^18446744073709551615")
+        );
+        assert_eq!((Unbounded, Unbounded), bounds("<=18446744073709551615"));
+        assert_eq!((Excluded(greatest.clone()), Unbounded), bounds(">18446744073709551615"));
+
+        let p = range::parse_predicate(">18446744073709551615.18446744073709551615")
+            .unwrap()
+            .unwrap();
+        assert!(!p.to_range().contains(&greatest));
+        assert!(!p.matches_prerelease(&greatest));
+    }
+
+    #[test]
+    fn test_predicate_range() {
+        use std::collections::BTreeMap;
+
+        let releases: BTreeMap<Version, &str> = [
+            ("0.9.0", "a"),
+            ("1.0.0", "b"),
+            ("1.2.0", "c"),
+            ("1.2.7", "d"),
+            ("1.3.0", "e"),
+            ("2.0.0", "f"),
+        ].iter()
+            .map(|&(version, name)| (v(version), name))
+            .collect();
+
+        let names = |predicate: &str| -> Vec<&str> {
+            let p = range::parse_predicate(predicate).unwrap().expect("non-empty");
+            releases.range(p.to_range()).map(|(_, &name)| name).collect()
+        };
+
+        assert_eq!(vec!["c", "d"], names("~1.2"));
+        assert_eq!(vec!["b", "c", "d", "e"], names("^1"));
+        assert_eq!(vec!["c", "d", "e", "f"], names(">1.0.0"));
+        assert_eq!(vec!["a", "b"], names("<=1.0.0"));
+        assert_eq!(vec!["d"], names("=1.2.7"));
+        assert_eq!(Vec::<&str>::new(), names("=1.2.8"));
+    }
+
+    #[test]
+    fn test_iter() {
+        let r = range::parse(">=1.2.0, <1.5.0, ~1.3").unwrap();
+        assert_eq!(3, r.iter().count());
+
+        let r = range::parse(">=1.2.0, <1.5.0 || * || ^2, <2.5.0").unwrap();
+        let majors: Vec<_> = r.iter().map(|p| p.major).collect();
+        assert_eq!(vec![1, 1, 2, 2], majors);
+
+        let mut count = 0;
+        for _ in &r {
+            count += 1;
+        }
+        assert_eq!(4, count);
+
+        assert_eq!(0, VersionReq::any().iter().count());
+    }
+
+    #[test]
+    fn test_parse_prefix() {
+        let (r, rest) = range::parse_prefix("1.2.3 rest-of-input").unwrap();
+
+        assert_eq!(range::parse("1.2.3").unwrap(), r);
+        assert_eq!(" rest-of-input", rest);
+
+        let (r, rest) = range::parse_prefix("=1.0.0").unwrap();
+        assert_eq!(range::parse("=1.0.0").unwrap(), r);
+        assert_eq!("", rest);
+
+        assert!(range::parse_prefix("rest").is_err());
+    }
+
+    #[test]
+    fn test_try_from() {
+        use core::convert::TryInto;
+
+        assert_eq!(range::parse("^1.2").unwrap(), VersionReq::try_from("^1.2").unwrap());
+
+        let r: Result<VersionReq, _> = ">=1.0.0, <2.0.0".try_into();
+        assert_eq!(range::parse(">=1.0.0, <2.0.0").unwrap(), r.unwrap());
+
+        let error = VersionReq::try_from("> 0.1.0,").unwrap_err();
+        assert_eq!("encountered empty predicate", error.to_string());
+    }
+
+    fn minimal(range: &str) -> Option<String> {
+        range::parse(range).unwrap().minimal_version().map(|v| v.to_string())
+    }
+
+    #[test]
+    fn test_minimal_version() {
+        assert_eq!(Some("1.2.3".to_string()), minimal("^1.2.3"));
+        assert_eq!(Some("0.0.3".to_string()), minimal("^0.0.3"));
+        assert_eq!(Some("1.2.0".to_string()), minimal("~1.2"));
+        assert_eq!(Some("1.0.0".to_string()), minimal("~1"));
+        assert_eq!(Some("1.2.0".to_string()), minimal("1.2.*"));
+        assert_eq!(Some("1.2.3".to_string()), minimal("=1.2.3"));
+        assert_eq!(Some("1.2.3-alpha".to_string()), minimal("=1.2.3-alpha"));
+        assert_eq!(Some("1.2.3".to_string()), minimal(">=1.2.3"));
+        assert_eq!(Some("1.2.4".to_string()), minimal(">1.2.3"));
+        assert_eq!(Some("1.3.0".to_string()), minimal(">1.2"));
+        assert_eq!(Some("1.2.3-alpha.0".to_string()), minimal(">1.2.3-alpha"));
+        assert_eq!(Some("1.2.3-0".to_string()), minimal(">1.2.2, <1.2.3-rc"));
+        assert_eq!(Some("1.3.0-0".to_string()), minimal(">1.2, <1.3.0-rc"));
+    }
+
+    #[test]
+    fn test_minimal_version_combined() {
+        assert_eq!(Some("1.5.0".to_string()), minimal(">=1.2.0, >=1.5.0, <2.0.0"));
+        assert_eq!(Some("1.4.0".to_string()), minimal("^1.2, ~1.4"));
+        assert_eq!(Some("1.0.0".to_string()), minimal("^2 || ^1"));
+        assert_eq!(None, minimal("<1.0.0 || ^2"));
+        assert_eq!(None, minimal("^2 || *"));
+        assert_eq!(Some("2.0.0".to_string()), minimal("<0.0.0 || ^2"));
+        assert_eq!(Some("2.0.0".to_string()), minimal(">=3.0.0, <1.0.0 || ^2"));
+    }
+
+    #[test]
+    fn test_minimal_version_none() {
+        assert_eq!(None, minimal("<2.0.0"));
+        assert_eq!(None, minimal("<=2.0.0"));
+        assert_eq!(None, minimal("*"));
+        assert_eq!(None, minimal(">=2.0.0, <1.0.0"));
+        assert_eq!(None, minimal("=1.0.0, =1.0.1"));
+        assert_eq!(None, minimal(">=1.2.3, <1.2.3-rc"));
+        let max = "18446744073709551615";
+        assert_eq!(None, minimal(&format!(">{}.{}.{}", max, max, max)));
+    }
+
+    #[test]
+    fn test_partial_prerelease_matches() {
+        let r = range::parse("0-Foo").unwrap();
+        let normalized = r.normalize();
+
+        for v in &["0.0.0-Foo", "0.0.0-Foo.1", "0.0.0-Goo", "0.0.0", "0.5.0"] {
+            let v = ::version::parse(v).unwrap();
+            assert!(r.matches(&v), "{}", v);
+            assert!(normalized.matches(&v), "{}", v);
+        }
+
+        for v in &["0.0.0-Eoo", "0.1.0-Foo", "1.0.0"] {
+            let v = ::version::parse(v).unwrap();
+            assert!(!r.matches(&v), "{}", v);
+            assert!(!normalized.matches(&v), "{}", v);
+        }
+
+        let v = ::version::parse_partial("1.2-rc.1").unwrap();
+        assert!(range::parse("~1.2-rc.1").unwrap().matches(&v));
+        assert!(range::parse("=1.2-rc.1").unwrap().matches(&v));
+        assert!(!range::parse("~1.2-rc.2").unwrap().matches(&v));
+    }
+
+    #[test]
+    fn test_op_bounds() {
+        let ops = [
+            (Op::Ex, true, true),
+            (Op::Gt, true, false),
+            (Op::GtEq, true, false),
+            (Op::Lt, false, true),
+            (Op::LtEq, false, true),
+            (Op::Tilde, true, true),
+            (Op::Compatible, true, true),
+            (Op::Wildcard(WildcardVersion::Minor), true, true),
+            (Op::Wildcard(WildcardVersion::Patch), true, true),
+            (Op::Pessimistic, true, true),
+        ];
+
+        for &(ref op, lower, upper) in ops.iter() {
+            assert_eq!(lower, op.is_lower_bound(), "{:?}", op);
+            assert_eq!(upper, op.is_upper_bound(), "{:?}", op);
+        }
+    }
+
+    #[test]
+    fn test_predicate_builder() {
+        let parsed = |input| range::parse_predicate(input).unwrap().unwrap();
+
+        assert_eq!(parsed("^1"), Predicate::builder(Op::Compatible, 1).build());
+        assert_eq!(parsed("~1.2"), Predicate::builder(Op::Tilde, 1).minor(2).build());
+        assert_eq!(
+            parsed("<1.2.3"),
+            Predicate::builder(Op::Lt, 1).minor(2).patch(3).build()
+        );
+        assert_eq!(
+            parsed("1.2.*"),
+            Predicate::builder(Op::Wildcard(WildcardVersion::Patch), 1).minor(2).build()
+        );
+
+        let built = Predicate::builder(Op::Ex, 1)
+            .minor(2)
+            .patch(3)
+            .pre(vec![Identifier::AlphaNumeric("beta".to_string()), Identifier::Numeric(2)])
+            .build_metadata(vec![Identifier::AlphaNumeric("meta".to_string())])
+            .build();
+        let expected = parsed("=1.2.3-beta.2+meta");
+
+        assert_eq!(expected, built);
+        assert_eq!(expected.build, built.build);
+        assert!(built.raw.is_empty());
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        assert_eq!(range::parse(">=1.2.3, <2"), range::parse_bytes(b">=1.2.3, <2"));
+        assert_eq!(range::parse("^1 || ~2.3"), range::parse_bytes(b"^1 || ~2.3"));
+        assert_eq!(range::parse("1.2.*"), range::parse_bytes(b"1.2.*"));
+
+        assert_eq!(
+            Err(parser::Error::Lexer(::lexer::Error::UnexpectedChar('\u{fffd}', 3))),
+            range::parse_bytes(b">=1\xc3")
+        );
+    }
+
+    #[test]
+    fn test_matches_prerelease() {
+        let r = range::parse(">=1.0.0").unwrap();
+        let beta = v("1.5.0-beta");
+
+        assert!(!r.matches(&beta));
+        assert!(r.matches_prerelease(&beta));
+        assert!(!r.matches_prerelease(&v("1.0.0-beta")));
+        assert!(r.matches_prerelease(&v("1.5.0")));
+
+        let prerelease_matches = |req: &str, version: &str| {
+            range::parse(req).unwrap().matches_prerelease(&v(version))
+        };
+
+        assert!(prerelease_matches("^1.2.3", "1.9.0-rc.1"));
+        assert!(!prerelease_matches("^1.2.3", "1.2.3-rc.1"));
+        assert!(prerelease_matches("^1.2.3", "2.0.0-rc.1"));
+        assert!(!prerelease_matches("^1.2.3", "2.0.0"));
+        assert!(prerelease_matches("~1.2", "1.2.7-alpha"));
+        assert!(prerelease_matches(">=1.2.3-alpha.2", "1.2.3-alpha.3"));
+        assert!(!prerelease_matches(">=1.2.3-alpha.2", "1.2.3-alpha.1"));
+        assert!(prerelease_matches("*", "0.1.0-alpha"));
+        assert!(prerelease_matches("<1 || >=2, <3", "2.1.0-beta"));
+        assert!(!prerelease_matches("<1 || >=2, <3", "1.1.0-beta"));
+    }
+
+    #[test]
+    fn test_explicit_major_wildcard() {
+        let major = Predicate::builder(Op::Wildcard(WildcardVersion::Major), 0).build();
+
+        for input in &["*", "x", "X", ">=*", "^*"] {
+            let r = range::parse_explicit_wildcard(input).unwrap();
+
+            assert_eq!(vec![major.clone()], r.predicates);
+            assert_eq!(*input, r.predicates[0].raw);
+            assert!(r.is_any());
+            assert!(range::parse(input).unwrap().predicates.is_empty());
+        }
+
+        let r = range::parse_explicit_wildcard("* || 1.* || 1.2.*").unwrap();
+        let ops: Vec<_> = r.iter().map(|p| p.op.clone()).collect();
+        assert_eq!(
+            vec![
+                Op::Wildcard(WildcardVersion::Major),
+                Op::Wildcard(WildcardVersion::Minor),
+                Op::Wildcard(WildcardVersion::Patch),
+            ],
+            ops
+        );
+        assert_eq!("* || 1.* || 1.2.*", r.to_string());
+
+        assert_eq!(
+            range::parse("1.*").unwrap(),
+            range::parse_explicit_wildcard("1.*").unwrap()
+        );
+        assert!(range::parse_explicit_wildcard("<*").is_err());
+        assert!(range::parse_explicit_wildcard("*.*").is_err());
+    }
+
+    #[test]
+    fn test_explicit_major_wildcard_matches() {
+        let r = range::parse_explicit_wildcard("*").unwrap();
+
+        assert!(r.matches(&v("0.0.0")));
+        assert!(r.matches(&v("1.2.3")));
+        assert!(!r.matches(&v("1.2.3-alpha")));
+        assert!(r.matches_prerelease(&v("1.2.3-alpha")));
+        assert_eq!(">=0.0.0", r.normalize().to_string());
+        assert_eq!(range::parse("*").unwrap().minimal_version(), r.minimal_version());
+        assert_eq!(None, r.minimal_version());
+
+        let r = range::parse_explicit_wildcard("*, >=1.2.0").unwrap();
+        assert_eq!(Some(v("1.2.0")), r.minimal_version());
+        assert!(WildcardVersion::Major < WildcardVersion::Minor);
+    }
+
+    #[test]
+    fn test_satisfied_by_any() {
+        let candidates: Vec<_> = ["0.9.0", "1.0.0", "1.3.0", "1.4.0-rc.1", "1.3.5", "2.0.0"]
+            .iter()
+            .map(|s| v(s))
+            .collect();
+        let best = |req: &str| range::parse(req).unwrap().satisfied_by_any(&candidates);
+
+        assert_eq!(Some(&v("1.3.5")), best("^1"));
+        assert_eq!(Some(&v("2.0.0")), best("*"));
+        assert_eq!(Some(&v("1.4.0-rc.1")), best(">=1.4.0-rc.1, <2"));
+        assert_eq!(Some(&v("0.9.0")), best("<1 || >=3"));
+        assert_eq!(None, best("^3"));
+        assert_eq!(None, range::parse("*").unwrap().satisfied_by_any(&[]));
+    }
+
+    #[test]
+    fn test_pessimistic() {
+        use lexer::Token;
+
+        assert_eq!(
+            Predicate::builder(Op::Pessimistic, 2).minor(2).build(),
+            range::parse_predicate("~> 2.2").unwrap().unwrap()
+        );
+
+        assert_eq!(vec![">=2.2.0", "<3.0.0"], comparators("~> 2.2"));
+        assert_eq!(vec![">=2.2.0", "<2.3.0"], comparators("~> 2.2.0"));
+        assert_eq!(vec![">=2.0.0", "<3.0.0"], comparators("~> 2"));
+        assert_eq!(vec![">=0.2.0", "<1.0.0"], comparators("~>0.2"));
+
+        assert!(matches("~> 2.2", "2.2.0"));
+        assert!(matches("~> 2.2", "2.9.1"));
+        assert!(!matches("~> 2.2", "2.1.9"));
+        assert!(!matches("~> 2.2", "3.0.0"));
+        assert!(matches("~> 2.2.0", "2.2.7"));
+        assert!(!matches("~> 2.2.0", "2.3.0"));
+        assert!(matches("~> 2", "2.5.0"));
+        assert!(!matches("~> 2", "3.0.0"));
+        assert!(matches("~> 2.2.0-rc.1", "2.2.0-rc.2"));
+        assert!(!matches("~> 2.2.0", "2.2.1-rc.1"));
+
+        // cargo's tilde only allows patch updates here.
+        assert!(!matches("~2.2", "2.9.1"));
+
+        assert_eq!("~>2.2", range::parse("~> 2.2").unwrap().to_string());
+        assert_eq!(
+            Err(parser::Error::UnexpectedOperator(Token::Pessimistic, Token::Eq, 2)),
+            range::parse("~>=2.2")
+        );
+    }
+
+    fn union(a: &str, b: &str) -> String {
+        range::parse(a).unwrap().union(&range::parse(b).unwrap()).to_string()
+    }
+
+    #[test]
+    fn test_union() {
+        assert_eq!("*", union("<1.0.0", ">=1.0.0"));
+        assert!(range::parse("<1.0.0").unwrap().union(&range::parse(">=1.0.0").unwrap()).is_any());
+        assert_eq!("*", union("*", "^1"));
 
+        assert_eq!("<1.0.0 || >=2.0.0", union(">=2.0.0", "<1.0.0"));
+        assert_eq!(">=1.0.0, <2.0.0 || >=3.0.0, <4.0.0", union("^1", "^3"));
+        assert_eq!(">=1.0.0, <3.0.0", union("^1", "^2"));
+        assert_eq!(">=1.0.0, <2.0.0", union("^1", "~1.5"));
+        assert_eq!(">=1.0.0, <=2.0.0", union("^1", "=2.0.0"));
+        assert_eq!("<1.0.0 || >1.0.0", union("<1.0.0", ">1.0.0"));
+        assert_eq!("=1.0.0", union("=1.0.0", "=1.0.0"));
         assert_eq!(
-            Predicate {
-                op: Op::Ex,
-                major: 0,
-                minor: Some(1),
-                patch: Some(0),
-                pre: vec![
-                    Identifier::AlphaNumeric(String::from("beta2")),
-                    Identifier::AlphaNumeric(String::from("a")),
-                ],
-            },
-            r.predicates[0]
+            ">=1.0.0, <2.0.0 || >=2.0.0-alpha, <2.0.0",
+            union("^1", ">=2.0.0-alpha, <2.0.0")
+        );
+        assert_eq!("<1.0.0", union("<1.0.0", ">2.0.0, <1.0.0"));
+        assert_eq!(
+            ">2.0.0, <1.0.0 || >2.0.0, <1.0.0",
+            union(">2.0.0, <1.0.0", ">2.0.0, <1.0.0")
         );
     }
 
     #[test]
-    pub fn test_parsing_greater_than() {
-        let r = range::parse("> 1.0.0").unwrap();
+    fn test_union_matches() {
+        let cases = [
+            ("<1.0.0", ">=1.2.0, <2.0.0"),
+            ("^1", "^0.5"),
+            ("~1.2.3", ">=1.2.3-rc.1, <1.3.0"),
+        ];
+        let versions = ["0.5.3", "1.0.0", "1.1.0", "1.2.3-rc.2", "1.2.5", "1.5.0", "2.0.0"];
 
-        assert_eq!(
-            Predicate {
-                op: Op::Gt,
-                major: 1,
-                minor: Some(0),
-                patch: Some(0),
-                pre: Vec::new(),
-            },
-            r.predicates[0]
-        );
+        for &(a, b) in cases.iter() {
+            let (a, b) = (range::parse(a).unwrap(), range::parse(b).unwrap());
+            let both = a.union(&b);
+
+            for version in versions.iter() {
+                let version = v(version);
+                assert_eq!(
+                    a.matches(&version) || b.matches(&version),
+                    both.matches(&version),
+                    "{} || {} against {}",
+                    a,
+                    b,
+                    version
+                );
+            }
+        }
+    }
+
+    fn semantically_eq(a: &str, b: &str) -> bool {
+        range::parse(a).unwrap().semantically_eq(&range::parse(b).unwrap())
     }
 
     #[test]
-    pub fn test_parsing_greater_than_01() {
-        let r = range::parse(">= 1.0.0").unwrap();
+    fn test_semantically_eq() {
+        assert!(semantically_eq("1.2.*", ">=1.2.0, <1.3.0"));
+        assert!(semantically_eq("1.2.*", "~1.2"));
+        assert!(semantically_eq("^1.2.3", ">=1.2.3, <2.0.0"));
+        assert!(semantically_eq("^1.2.3", "<2.0.0, >=1.2.3"));
+        assert!(semantically_eq("^1 || ^2", ">=1.0.0, <3.0.0"));
+        assert!(semantically_eq("^2 || ^1", "^1 || ^2"));
+        assert!(semantically_eq("<1.0.0 || >=1.0.0", "*"));
+        assert!(semantically_eq("=1.2.3", ">=1.2.3, <=1.2.3"));
+        assert!(semantically_eq(">=1.0.0-alpha, <2", "<2.0.0, >=1.0.0-alpha"));
 
-        assert_eq!(
-            Predicate {
-                op: Op::GtEq,
-                major: 1,
-                minor: Some(0),
-                patch: Some(0),
-                pre: Vec::new(),
-            },
-            r.predicates[0]
-        );
+        assert!(!semantically_eq("^1.2.3", "~1.2.3"));
+        assert!(!semantically_eq("1.2.*", ">=1.2.0, <=1.3.0"));
+        assert!(!semantically_eq("<1.0.0 || >1.0.0", "*"));
+        assert!(!semantically_eq(">=1.0.0-alpha", ">=1.0.0"));
     }
 
     #[test]
-    pub fn test_parsing_greater_than_02() {
-        let r = range::parse(">= 2.1.0-alpha2").unwrap();
+    fn test_compatible_zero() {
+        assert!(matches("^0.0.0", "0.0.0"));
+        assert!(matches("^0.0.0", "0.0.0+build"));
+        assert!(!matches("^0.0.0", "0.0.1"));
+        assert!(!matches("^0.0.0", "0.1.0"));
+        assert!(!matches("^0.0.0", "1.0.0"));
+        assert!(!matches("^0.0.0", "0.0.0-alpha"));
+        assert_eq!(vec![">=0.0.0", "<0.0.1"], comparators("^0.0.0"));
+    }
 
-        assert_eq!(
-            Predicate {
-                op: Op::GtEq,
-                major: 2,
-                minor: Some(1),
-                patch: Some(0),
-                pre: vec![Identifier::AlphaNumeric(String::from("alpha2"))],
-            },
-            r.predicates[0]
-        );
+    #[test]
+    fn test_exact_partial() {
+        assert!(matches("=1.2", "1.2.0"));
+        assert!(matches("=1.2", "1.2.9"));
+        assert!(!matches("=1.2", "1.3.0"));
+        assert!(!matches("=1.2", "1.1.9"));
+        assert!(!matches("=1.2", "1.2.7-alpha"));
+
+        assert!(matches("=1", "1.0.0"));
+        assert!(matches("=1", "1.9.9"));
+        assert!(!matches("=1", "2.0.0"));
+        assert!(!matches("=1", "0.9.9"));
+
+        assert!(matches("=1.2.0", "1.2.0"));
+        assert!(!matches("=1.2.0", "1.2.7"));
+
+        assert_eq!(vec![">=1.2.0", "<1.3.0"], comparators("=1.2"));
+        assert_eq!(vec![">=1.0.0", "<2.0.0"], comparators("=1"));
+
+        let p = range::parse_predicate("=1.2").unwrap().unwrap();
+        assert_eq!((Some(2), None), (p.minor, p.patch));
+        assert_eq!("=1.2", p.to_string());
+
+        // the same versions as the wildcard, but the operator is kept.
+        assert_eq!(Op::Ex, p.op);
+        assert!(range::parse("=1.2").unwrap().semantically_eq(&range::parse("1.2.*").unwrap()));
     }
 
     #[test]
-    pub fn test_parsing_less_than() {
-        let r = range::parse("< 1.0.0").unwrap();
+    fn test_parse_relaxed() {
+        let relaxed = |input| range::parse_relaxed(input).map(|r| r.to_string());
 
-        assert_eq!(
-            Predicate {
-                op: Op::Lt,
-                major: 1,
-                minor: Some(0),
-                patch: Some(0),
-                pre: Vec::new(),
-            },
-            r.predicates[0]
-        );
+        assert_eq!(Ok(">=1.0.0".to_string()), relaxed(">=1.0.0, "));
+        assert_eq!(Ok(">=1.0.0".to_string()), relaxed(">=1.0.0,"));
+        assert_eq!(Ok("^1.2.3, <2".to_string()), relaxed("1.2.3 ,  <2"));
+        assert_eq!(Ok(">=1, <2".to_string()), relaxed(", >=1,, , <2 ,"));
+        assert_eq!(Ok(">=1".to_string()), relaxed("  >=1  "));
+        assert_eq!(Ok("^1 || ^2".to_string()), relaxed("1, || , 2,"));
+        assert_eq!(Ok("*".to_string()), relaxed(","));
+
+        assert_eq!(Err(parser::Error::EmptyRange), relaxed("1 || ,"));
+        assert_eq!(Err(parser::Error::EmptyRange), relaxed(" , || 1"));
+        assert!(relaxed(">=1.0.0, foo").is_err());
+
+        assert!(range::parse(">=1.0.0, ").is_err());
+        assert!(range::parse(", >=1, <2").is_err());
+        assert!(range::parse(">=1,, <2").is_err());
+        assert!(range::parse("  >=1").is_err());
+        assert_eq!(range::parse("1.2.3 ,  <2"), range::parse_relaxed("1.2.3 ,  <2"));
     }
 
     #[test]
-    pub fn test_parsing_less_than_eq() {
-        let r = range::parse("<= 2.1.0-alpha2").unwrap();
+    fn test_predicate_compare() {
+        let compare = |predicate: &str, version: &str| {
+            range::parse_predicate(predicate).unwrap().unwrap().compare(&v(version))
+        };
 
-        assert_eq!(
-            Predicate {
-                op: Op::LtEq,
-                major: 2,
-                minor: Some(1),
-                patch: Some(0),
-                pre: vec![Identifier::AlphaNumeric(String::from("alpha2"))],
-            },
-            r.predicates[0]
-        );
+        assert_eq!(PredicateMatch::Below, compare(">1.2.3", "1.0.0"));
+        assert_eq!(PredicateMatch::Below, compare(">1.2.3", "1.2.3"));
+        assert_eq!(PredicateMatch::Matches, compare(">1.2.3", "1.2.4"));
+        assert_eq!(PredicateMatch::Matches, compare(">1.2.3", "99.0.0"));
+
+        assert_eq!(PredicateMatch::Matches, compare("<2.0.0", "1.9.9"));
+        assert_eq!(PredicateMatch::Matches, compare("<2.0.0", "0.0.0"));
+        assert_eq!(PredicateMatch::Above, compare("<2.0.0", "2.0.0"));
+        assert_eq!(PredicateMatch::Above, compare("<2.0.0", "2.0.1"));
+
+        assert_eq!(PredicateMatch::Matches, compare(">=1.2.3", "1.2.3"));
+        assert_eq!(PredicateMatch::Below, compare(">=1.2.3", "1.2.3-alpha"));
+        assert_eq!(PredicateMatch::Matches, compare("<=2.0.0", "2.0.0"));
+        assert_eq!(PredicateMatch::Above, compare("<=2.0.0", "2.0.1"));
+
+        assert_eq!(PredicateMatch::Below, compare("=1.2.3", "1.2.2"));
+        assert_eq!(PredicateMatch::Matches, compare("=1.2.3", "1.2.3"));
+        assert_eq!(PredicateMatch::Above, compare("=1.2.3", "1.2.4"));
+
+        // within the bounds, even though prereleases do not match.
+        assert_eq!(PredicateMatch::Matches, compare("^1.2.3", "1.5.0-beta"));
     }
 
     #[test]
-    pub fn test_parsing_tilde() {
-        let r = range::parse("~1").unwrap();
+    fn test_predicates_sorted() {
+        let sorted = |input| range::parse(input).unwrap().predicates_sorted();
+
+        assert_eq!(sorted(">=1, <2"), sorted("<2, >=1"));
+        assert_eq!(sorted(">=1.2.3, <2.0.0, ~1.5"), sorted("~1.5 <2.0.0 >=1.2.3"));
+        assert_eq!(sorted(">1, >2"), sorted(">2, >1"));
+        assert_ne!(sorted(">=1, <2"), sorted(">=1, <3"));
+        assert_eq!(sorted("^1 || >=2, <3"), sorted("<3, >=2 || ^1"));
+        assert_ne!(sorted("^1 || ^5"), sorted("^1"));
+
+        let ops: Vec<_> = sorted("<2, >=1, =1.5").remove(0).into_iter().map(|p| p.op).collect();
+        assert_eq!(vec![Op::Ex, Op::GtEq, Op::Lt], ops);
+
+        let versions: Vec<_> = sorted(">3, >1, >2")[0].iter().map(|p| p.major).collect();
+        assert_eq!(vec![1, 2, 3], versions);
+
+        assert_eq!(vec![Vec::<Predicate>::new()], sorted("*"));
+    }
+
+    fn simple_comparators(predicate: &str) -> Vec<SimpleComparator> {
+        range::parse_predicate(predicate)
+            .unwrap()
+            .expect("non-empty")
+            .to_simple_comparators()
+    }
 
+    #[test]
+    fn test_simple_comparators() {
         assert_eq!(
-            Predicate {
-                op: Op::Tilde,
-                major: 1,
-                minor: None,
-                patch: None,
-                pre: Vec::new(),
-            },
-            r.predicates[0]
+            vec![
+                SimpleComparator {
+                    op: SimpleOp::GtEq,
+                    version: Version::new(1, 2, 3),
+                },
+                SimpleComparator {
+                    op: SimpleOp::Lt,
+                    version: Version::new(2, 0, 0),
+                },
+            ],
+            simple_comparators("^1.2.3")
         );
+
+        let display = |predicate| -> Vec<String> {
+            simple_comparators(predicate).iter().map(ToString::to_string).collect()
+        };
+
+        assert_eq!(vec!["=1.2.3"], display("=1.2.3"));
+        assert_eq!(vec![">=1.2.0", "<1.3.0"], display("~1.2"));
+        assert_eq!(vec![">1.2.3-alpha"], display(">1.2.3-alpha"));
+        assert_eq!(vec!["<=1.2.3"], display("<=1.2.3+build"));
+        assert_eq!(vec![">=2.0.0"], display(">1"));
+
+        for predicate in &["^1.2.3", "~1.2", "=1.2.3", ">1", "<=2.0.0", "1.*", "~> 2.2"] {
+            let p = range::parse_predicate(predicate).unwrap().unwrap();
+            let ops: Vec<Op> = p.to_simple_comparators().into_iter().map(|c| c.op.into()).collect();
+            let expected: Vec<Op> = p.to_comparators().into_iter().map(|c| c.op).collect();
+            assert_eq!(expected, ops, "{}", predicate);
+        }
     }
 
     #[test]
-    pub fn test_parsing_compatible() {
-        let r = range::parse("^0").unwrap();
+    fn test_simple_comparator_matches() {
+        let c = |op, version: &str| SimpleComparator { op, version: v(version) };
+
+        assert!(c(SimpleOp::Eq, "1.2.3").matches(&v("1.2.3+build")));
+        assert!(!c(SimpleOp::Eq, "1.2.3").matches(&v("1.2.4")));
+        assert!(c(SimpleOp::Gt, "1.2.3").matches(&v("1.2.4")));
+        assert!(!c(SimpleOp::Gt, "1.2.3").matches(&v("1.2.3")));
+        assert!(c(SimpleOp::GtEq, "1.2.3").matches(&v("1.2.3")));
+        assert!(!c(SimpleOp::GtEq, "1.2.3").matches(&v("1.2.3-rc.1")));
+        assert!(c(SimpleOp::Lt, "2.0.0").matches(&v("2.0.0-alpha")));
+        assert!(!c(SimpleOp::Lt, "2.0.0").matches(&v("2.0.0")));
+        assert!(c(SimpleOp::LtEq, "2.0.0").matches(&v("2.0.0")));
+        assert!(!c(SimpleOp::LtEq, "2.0.0").matches(&v("2.0.1")));
+    }
 
+    #[test]
+    fn test_parse_unicode_digits() {
         assert_eq!(
-            Predicate {
-                op: Op::Compatible,
-                major: 0,
-                minor: None,
-                patch: None,
-                pre: Vec::new(),
-            },
-            r.predicates[0]
+            Err(parser::Error::Lexer(::lexer::Error::UnexpectedChar('\u{0661}', 2))),
+            range::parse(">=\u{0661}.0.0")
         );
+        assert!(range::parse("^1.\u{ff12}").is_err());
+        assert!(range::parse("1.2.3 || \u{0661}").is_err());
+    }
+
+    fn is_subset(req: &str, other: &str) -> bool {
+        range::parse(req).unwrap().is_subset_of(&range::parse(other).unwrap())
     }
 
     #[test]
-    fn test_parsing_blank() {
-        let r = range::parse("").unwrap();
-        assert!(r.predicates.is_empty());
+    fn test_is_subset_of() {
+        assert!(is_subset("^1.2.3", ">=1.0.0"));
+        assert!(is_subset("^1.2.3", "^1"));
+        assert!(is_subset("^1.2.3", "^1.2.3"));
+        assert!(is_subset("1.2.*", "~1.2"));
+        assert!(is_subset("~1.2.3", ">=1.2.3, <1.3.0"));
+        assert!(is_subset("^1", "*"));
+        assert!(is_subset("*", "*"));
+
+        assert!(!is_subset("^1", "^2"));
+        assert!(!is_subset("^1", "^1.2.3"));
+        assert!(!is_subset(">=1.0.0", "^1"));
+        assert!(!is_subset("*", "^1"));
+        assert!(!is_subset("<=2.0.0", "<2.0.0"));
+        assert!(!is_subset(">1.0.0", "~1"));
+        assert!(is_subset(">1.0.0, <1.5.0", ">=1.0.0"));
+        assert!(!is_subset(">=1.0.0", ">1.0.0"));
     }
 
     #[test]
-    fn test_parsing_wildcard() {
-        let r = range::parse("*").unwrap();
-        assert!(r.predicates.is_empty());
+    fn test_is_subset_of_exact() {
+        assert!(is_subset("=1.5.0", "^1"));
+        assert!(is_subset("=1.5.0", ">=1.5.0, <=1.5.0"));
+        assert!(is_subset("=1.0.0", ">=1.0.0"));
+        assert!(is_subset("=1.5.0", "^0.1 || ~1.5"));
+
+        assert!(!is_subset("=2.0.0", "^1"));
+        assert!(!is_subset("=1.0.0", ">1.0.0"));
+        assert!(!is_subset("^1", "=1.5.0"));
     }
 
     #[test]
-    fn test_uppercase_prereleases() {
-        assert_eq!(
-            vec![Identifier::AlphaNumeric("Foo".to_string())],
-            range::parse("0-Foo").unwrap().predicates[0].pre
-        );
+    fn test_is_subset_of_groups() {
+        assert!(is_subset("^1 || ^2", ">=1.0.0, <3.0.0"));
+        assert!(is_subset(">=1.0.0, <3.0.0", "^1 || ^2"));
+        assert!(is_subset("^1.5 || ^3", "^1 || ^3.1 || ^3.0"));
 
-        assert_eq!(
-            vec![Identifier::AlphaNumeric("X".to_string())],
-            range::parse("0-X").unwrap().predicates[0].pre
-        );
+        assert!(!is_subset("^1 || ^3", ">=1.0.0, <3.0.0"));
+        assert!(!is_subset(">=1.0.0, <4.0.0", "^1 || ^3"));
     }
 
     #[test]
-    fn test_empty_prerelease() {
-        assert!(range::parse("0-").is_err());
+    fn test_is_subset_of_unsatisfiable() {
+        assert!(is_subset(">2.0.0, <1.0.0", "=5.0.0"));
+        assert!(is_subset(">2.0.0, <1.0.0 || ^1", "^1"));
+        assert!(!is_subset("^1", ">2.0.0, <1.0.0"));
     }
 
     #[test]
-    fn test_parsing_x() {
-        let r = range::parse("x").unwrap();
-        assert!(r.predicates.is_empty());
+    fn test_is_subset_of_prerelease() {
+        assert!(is_subset("^1.2.3", ">=1.2.3-alpha"));
+        assert!(is_subset(">=1.2.3-beta, <1.3.0", ">=1.2.3-alpha"));
+        assert!(is_subset("=1.2.3-alpha", "^1.2.3-alpha"));
+
+        // `^1` does not match prereleases like `1.2.3-beta`.
+        assert!(!is_subset(">=1.2.3-alpha, <1.3.0", "^1"));
+        assert!(!is_subset(">=1.2.3-alpha", ">=1.2.3-beta"));
+        assert!(!is_subset("=1.2.3-alpha", "^1.2.3"));
     }
 
     #[test]
-    fn test_parsing_capital_x() {
-        let r = range::parse("X").unwrap();
-        assert!(r.predicates.is_empty());
+    fn test_is_valid() {
+        let inputs = [
+            "",
+            "*",
+            "^1.2.3",
+            ">=1.2.3, <2",
+            ">=1.2.3 <2",
+            "~1 || 2.x",
+            "=1.2.3-alpha.1+build",
+            "~> 2.2",
+            ">=1.2.3,",
+            ", 1",
+            "|| 1",
+            "1 ||",
+            "<*",
+            "1.2.3-01",
+            "1.*.3",
+            "foo",
+            "1 || || 2",
+            " ^1 ||  ~2.0 ",
+            ">1, , <2",
+            ">=1.0.0 <2.0.0 || =3",
+            "1.2.3 a",
+        ];
+
+        for input in &inputs {
+            assert_eq!(range::parse(input).is_ok(), range::is_valid(input), "{}", input);
+        }
+    }
+
+    fn max_below(req: &str, ceiling: &str) -> Option<String> {
+        range::parse(req)
+            .unwrap()
+            .max_version_below(&v(ceiling))
+            .map(|version| version.to_string())
     }
 
-    /// TODO: this should probably be using WildcardVersion::Minor
     #[test]
-    fn test_parsing_wildcard_star_star() {
-        let r = range::parse("1.*.*").unwrap();
+    fn test_max_version_below_caret() {
+        let max = u64::MAX;
 
-        assert_eq!(
-            Predicate {
-                op: Op::Wildcard(WildcardVersion::Patch),
-                major: 1,
-                minor: None,
-                patch: None,
-                pre: Vec::new(),
-            },
-            r.predicates[0]
-        );
+        assert_eq!(Some(format!("1.{}.{}", max, max)), max_below("^1.2.3", "3.0.0"));
+        assert_eq!(Some(format!("1.4.{}", max)), max_below("^1.2.3", "1.5.0"));
+        assert_eq!(Some(String::from("1.4.6")), max_below("^1.2.3", "1.4.7"));
+        assert_eq!(Some(String::from("1.4.6")), max_below("^1.2.3", "1.4.7-alpha"));
+        assert_eq!(Some(String::from("0.2.4")), max_below("^0.2.3", "0.2.5"));
+        assert_eq!(Some(String::from("0.0.3")), max_below("^0.0.3", "1.0.0"));
     }
 
     #[test]
-    fn test_parsing_minor_wildcard_star() {
-        let r = range::parse("1.*").unwrap();
+    fn test_max_version_below_ceiling_below_range() {
+        assert_eq!(None, max_below("^1.2.3", "1.2.3"));
+        assert_eq!(None, max_below("^1.2.3", "1.0.0"));
+        assert_eq!(None, max_below(">=1.0.0", "0.9.0"));
+        assert_eq!(None, max_below("=1.2.3", "1.2.3"));
+        assert_eq!(None, max_below("*", "0.0.0"));
+        assert_eq!(None, max_below(">2.0.0, <1.0.0", "5.0.0"));
+    }
 
-        assert_eq!(
-            Predicate {
-                op: Op::Wildcard(WildcardVersion::Minor),
-                major: 1,
-                minor: None,
-                patch: None,
-                pre: Vec::new(),
-            },
-            r.predicates[0]
-        );
+    #[test]
+    fn test_max_version_below_bounds() {
+        assert_eq!(Some(String::from("1.2.3")), max_below("=1.2.3", "2.0.0"));
+        assert_eq!(Some(String::from("1.9.0")), max_below("<=1.9.0", "2.0.0"));
+        assert_eq!(Some(String::from("1.2.2")), max_below("*", "1.2.3"));
+        assert_eq!(Some(String::from("1.2.2")), max_below(">=1.0.0", "1.2.3"));
+        assert_eq!(Some(String::from("1.2.3-alpha")), max_below("=1.2.3-alpha", "1.2.3"));
+        assert_eq!(Some(String::from("2.5.0")), max_below("^1 || <=2.5.0, >2.1", "3.0.0"));
+        assert_eq!(Some(String::from("1.1.9")), max_below("~1.1 || ^2", "1.1.10"));
     }
 
     #[test]
-    fn test_parsing_minor_wildcard_star_patch() {
-        let r = range::parse("1.*.0").unwrap();
+    fn test_from_version() {
+        let req = VersionReq::from(&v("1.2.3"));
 
-        assert_eq!(
-            Predicate {
-                op: Op::Wildcard(WildcardVersion::Minor),
-                major: 1,
-                minor: None,
-                patch: Some(0),
-                pre: Vec::new(),
-            },
-            r.predicates[0]
-        );
+        assert_eq!(1, req.predicates.len());
+        assert!(req.or_groups.is_empty());
+
+        let p = &req.predicates[0];
+        assert_eq!(Op::Compatible, p.op);
+        assert_eq!((1, Some(2), Some(3)), (p.major, p.minor, p.patch));
+        assert!(p.pre.is_empty());
+        assert!(p.build.is_empty());
+
+        assert_eq!(range::parse("1.2.3").unwrap(), req);
+        assert_eq!(range::parse("^0.0.1-rc.1").unwrap(), VersionReq::from(v("0.0.1-rc.1+b")));
+        assert_eq!("^0.2.0", VersionReq::from(v("0.2.0")).to_string());
     }
 
     #[test]
-    fn test_parsing_minor_wildcard_x() {
-        let r = range::parse("1.x").unwrap();
+    fn test_wildcard_shape() {
+        let shape = |input| range::parse(input).unwrap().predicates[0].wildcard();
+
+        assert_eq!(Some(WildcardShape::LowerX), shape("1.x"));
+        assert_eq!(Some(WildcardShape::UpperX), shape("1.2.X"));
+        assert_eq!(Some(WildcardShape::Star), shape("=1.*"));
+        assert_eq!(Some(WildcardShape::UpperX), shape("= 1.X"));
+        assert_eq!(Some(WildcardShape::LowerX), shape("1.x.*"));
+        assert_eq!(None, shape("1.2.3"));
+        assert_eq!(None, shape(">=1.x"));
 
         assert_eq!(
-            Predicate {
-                op: Op::Wildcard(WildcardVersion::Minor),
-                major: 1,
-                minor: None,
-                patch: None,
-                pre: Vec::new(),
-            },
-            r.predicates[0]
+            Some(WildcardShape::UpperX),
+            range::parse_explicit_wildcard("X").unwrap().predicates[0].wildcard()
         );
+
+        // the shape does not take part in comparisons.
+        assert_eq!(range::parse("1.x").unwrap(), range::parse("1.*").unwrap());
     }
 
     #[test]
-    fn test_parsing_minor_wildcard_capital_x() {
-        let r = range::parse("1.X").unwrap();
+    fn test_to_faithful_string() {
+        let faithful = |input| range::parse(input).unwrap().to_faithful_string();
 
+        assert_eq!("1.x", faithful("1.x"));
+        assert_eq!("1.x", faithful("1.x.x"));
+        assert_eq!("1.2.X", faithful("1.2.X"));
+        assert_eq!("1.*", faithful("1.*"));
+        assert_eq!(">=1.2.3, 2.x || 3.4.X", faithful(">=1.2.3, 2.x || 3.4.X"));
+        assert_eq!("1.*", range::parse("1.x").unwrap().to_string());
         assert_eq!(
-            Predicate {
-                op: Op::Wildcard(WildcardVersion::Minor),
-                major: 1,
-                minor: None,
-                patch: None,
-                pre: Vec::new(),
-            },
-            r.predicates[0]
+            "x",
+            range::parse_explicit_wildcard("x").unwrap().to_faithful_string()
         );
+
+        // predicates which were not parsed use `*`.
+        let p = Predicate {
+            op: Op::Wildcard(WildcardVersion::Patch),
+            major: 1,
+            minor: Some(2),
+            patch: None,
+            pre: Vec::new(),
+            build: Vec::new(),
+            raw: String::new(),
+        };
+        assert_eq!("1.2.*", p.to_faithful_string());
     }
 
     #[test]
-    fn test_parsing_patch_wildcard_star() {
-        let r = range::parse("1.2.*").unwrap();
+    fn test_whitespace_separated() {
+        let r = range::parse(">=1.2.3 <2.0.0").unwrap();
 
-        assert_eq!(
-            Predicate {
-                op: Op::Wildcard(WildcardVersion::Patch),
-                major: 1,
-                minor: Some(2),
-                patch: None,
-                pre: Vec::new(),
-            },
-            r.predicates[0]
-        );
+        assert_eq!(range::parse(">=1.2.3, <2.0.0").unwrap(), r);
+        assert_eq!(2, r.predicates.len());
+        assert!(r.or_groups.is_empty());
+        assert_eq!(">=1.2.3, <2.0.0", r.to_string());
+
+        assert_eq!(r, range::parse(">=1.2.3\t\n<2.0.0").unwrap());
+        assert_eq!(r, range::parse(">= 1.2.3   < 2.0.0").unwrap());
+        assert_eq!(3, range::parse("^1 >=1.2, <1.5").unwrap().predicates.len());
+        assert_eq!(3, range::parse("^1, >=1.2 <1.5").unwrap().predicates.len());
+
+        assert!(r.matches(&v("1.5.0")));
+        assert!(!r.matches(&v("2.0.0")));
+        assert!(!r.matches(&v("1.2.2")));
     }
 
     #[test]
-    fn test_parsing_patch_wildcard_x() {
-        let r = range::parse("1.2.x").unwrap();
+    fn test_whitespace_separated_or() {
+        let r = range::parse(">=1.2.3 <2.0.0 || ^3").unwrap();
 
-        assert_eq!(
-            Predicate {
-                op: Op::Wildcard(WildcardVersion::Patch),
-                major: 1,
-                minor: Some(2),
-                patch: None,
-                pre: Vec::new(),
-            },
-            r.predicates[0]
-        );
+        assert_eq!(range::parse(">=1.2.3, <2.0.0 || ^3").unwrap(), r);
+        assert_eq!(range::parse(">=1.2.3 <2.0.0||^3").unwrap(), r);
+        assert_eq!(2, r.predicates.len());
+        assert_eq!(1, r.or_groups.len());
+        assert_eq!(1, r.or_groups[0].len());
+        assert_eq!(">=1.2.3, <2.0.0 || ^3", r.to_string());
+
+        for version in &["1.2.3", "1.9.9", "3.0.0", "3.9.0"] {
+            assert!(r.matches(&v(version)), "{}", version);
+        }
+        for version in &["1.2.2", "2.0.0", "2.5.0", "4.0.0"] {
+            assert!(!r.matches(&v(version)), "{}", version);
+        }
+
+        let r = range::parse("^1 || >=3.1 <3.5 >3.2").unwrap();
+        assert_eq!(1, r.predicates.len());
+        assert_eq!(3, r.or_groups[0].len());
+        assert!(r.matches(&v("3.3.0")));
+        assert!(!r.matches(&v("3.2.0")));
+    }
+
+    fn predicates(input: &str) -> Vec<Predicate> {
+        range::parse(input).unwrap().predicates
     }
 
     #[test]
-    fn test_parsing_patch_wildcard_capital_x() {
-        let r = range::parse("1.2.X").unwrap();
+    fn test_from_predicates() {
+        let r = VersionReq::from_predicates(predicates(">=1.2.3, <2.0.0, ~1.4")).unwrap();
+        assert_eq!(range::parse(">=1.2.3, <2.0.0, ~1.4").unwrap(), r);
+        assert!(r.or_groups.is_empty());
 
-        assert_eq!(
-            Predicate {
-                op: Op::Wildcard(WildcardVersion::Patch),
-                major: 1,
-                minor: Some(2),
-                patch: None,
-                pre: Vec::new(),
-            },
-            r.predicates[0]
-        );
+        assert!(VersionReq::from_predicates(predicates("=1.2.3, ^1")).is_ok());
+        assert!(VersionReq::from_predicates(predicates(">=1.0.0, <=1.0.0")).is_ok());
+        assert!(VersionReq::from_predicates(predicates("1.2.*, >1.2.3-alpha")).is_ok());
+        assert_eq!(Ok(VersionReq::any()), VersionReq::from_predicates(Vec::new()));
     }
 
     #[test]
-    pub fn test_multiple_01() {
-        let r = range::parse("> 0.0.9, <= 2.5.3").unwrap();
+    fn test_from_predicates_contradictory() {
+        let contradictory = [
+            "=1.0.0, =2.0.0",
+            ">2.0.0, <1.0.0",
+            ">1.0.0, <=1.0.0",
+            "^1, ^2",
+            "~1.2, 1.3.*",
+            "=1.2.3-alpha, =1.2.3",
+        ];
 
-        assert_eq!(
-            Predicate {
-                op: Op::Gt,
-                major: 0,
-                minor: Some(0),
-                patch: Some(9),
-                pre: Vec::new(),
-            },
-            r.predicates[0]
-        );
+        for input in &contradictory {
+            assert_eq!(
+                Err(String::from("Predicates can not match any version")),
+                VersionReq::from_predicates(predicates(input)),
+                "{}",
+                input
+            );
+        }
+    }
 
-        assert_eq!(
-            Predicate {
-                op: Op::LtEq,
-                major: 2,
-                minor: Some(5),
-                patch: Some(3),
-                pre: Vec::new(),
-            },
-            r.predicates[1]
-        );
+    fn pinned(input: &str) -> Option<String> {
+        range::parse(input).unwrap().pinned_version().map(|version| version.to_string())
     }
 
     #[test]
-    pub fn test_multiple_02() {
-        let r = range::parse("0.3.0, 0.4.0").unwrap();
+    fn test_pinned_version() {
+        assert_eq!(Some(String::from("1.2.3")), pinned("=1.2.3"));
+        assert_eq!(Some(String::from("1.2.3")), pinned("=1.2.3+build"));
+        assert_eq!(Some(String::from("0.0.3")), pinned("^0.0.3"));
+        assert_eq!(Some(String::from("1.2.3-alpha")), pinned("=1.2.3-alpha"));
+        assert_eq!(Some(String::from("1.2.3")), pinned(">=1.2.3, <=1.2.3"));
+        assert_eq!(Some(String::from("1.2.3")), pinned(">1.2.2, <1.2.4"));
+        assert_eq!(Some(String::from("1.2.3")), pinned("=1.2.3 || =1.2.3"));
+        assert_eq!(Some(String::from("1.2.3")), pinned("^1.2.3, <1.2.4"));
 
-        assert_eq!(
-            Predicate {
-                op: Op::Compatible,
-                major: 0,
-                minor: Some(3),
-                patch: Some(0),
-                pre: Vec::new(),
-            },
-            r.predicates[0]
-        );
+        assert_eq!(None, pinned("^1.2.3"));
+        assert_eq!(None, pinned("~1.2.3"));
+        assert_eq!(None, pinned("^0.0"));
+        assert_eq!(None, pinned("1.2.*"));
+        assert_eq!(None, pinned(">=1.2.3"));
+        assert_eq!(None, pinned("*"));
+        assert_eq!(None, pinned("=1.2.3 || =1.2.4"));
+        assert_eq!(None, pinned(">2.0.0, <1.0.0"));
+        assert_eq!(None, pinned(">=1.2.3-alpha, <1.2.3"));
+    }
 
-        assert_eq!(
-            Predicate {
-                op: Op::Compatible,
-                major: 0,
-                minor: Some(4),
-                patch: Some(0),
-                pre: Vec::new(),
-            },
-            r.predicates[1]
-        );
+    #[test]
+    fn test_parse_collect_errors() {
+        for input in &["", "*", "^1.2.3", ">=1.2.3, <2 || ~3.1 3.1.*", "1.x || 2"] {
+            let (r, errors) = range::parse_collect_errors(input);
+            assert_eq!(range::parse(input).ok(), r, "{}", input);
+            assert!(errors.is_empty(), "{}", input);
+        }
     }
 
     #[test]
-    pub fn test_multiple_03() {
-        let r = range::parse("<= 0.2.0, >= 0.5.0").unwrap();
+    fn test_parse_collect_errors_multiple() {
+        let input = ">=1.02, , <2 || ^x.1 || || 1.2.3-";
+        let (r, errors) = range::parse_collect_errors(input);
 
+        assert_eq!(None, r);
         assert_eq!(
-            Predicate {
-                op: Op::LtEq,
-                major: 0,
-                minor: Some(2),
-                patch: Some(0),
-                pre: Vec::new(),
-            },
-            r.predicates[0]
+            vec![Some(4), Some(6), Some(18), None, Some(33)],
+            errors.iter().map(ParseError::position).collect::<Vec<_>>()
         );
+        assert_eq!("numeric identifier with leading zero: 02", errors[0].message());
+        assert_eq!("encountered empty predicate", errors[1].message());
+        assert_eq!("encountered empty range", errors[3].message());
+        assert_eq!("encountered empty identifier", errors[4].message());
 
+        // the first error is the one `parse` reports.
         assert_eq!(
-            Predicate {
-                op: Op::GtEq,
-                major: 0,
-                minor: Some(5),
-                patch: Some(0),
-                pre: Vec::new(),
-            },
-            r.predicates[1]
+            ParseError::from(range::parse(input).unwrap_err()),
+            errors[0]
         );
     }
 
     #[test]
-    pub fn test_multiple_04() {
-        let r = range::parse("0.1.0, 0.1.4, 0.1.6").unwrap();
-
-        assert_eq!(
-            Predicate {
-                op: Op::Compatible,
-                major: 0,
-                minor: Some(1),
-                patch: Some(0),
-                pre: Vec::new(),
-            },
-            r.predicates[0]
-        );
+    fn test_parse_collect_errors_empty_predicates() {
+        let (_, errors) = range::parse_collect_errors(", 1");
+        assert_eq!(vec![Some(0)], errors.iter().map(ParseError::position).collect::<Vec<_>>());
 
-        assert_eq!(
-            Predicate {
-                op: Op::Compatible,
-                major: 0,
-                minor: Some(1),
-                patch: Some(4),
-                pre: Vec::new(),
-            },
-            r.predicates[1]
-        );
+        let (_, errors) = range::parse_collect_errors("1 ,");
+        assert_eq!(vec![Some(2)], errors.iter().map(ParseError::position).collect::<Vec<_>>());
 
-        assert_eq!(
-            Predicate {
-                op: Op::Compatible,
-                major: 0,
-                minor: Some(1),
-                patch: Some(6),
-                pre: Vec::new(),
-            },
-            r.predicates[2]
-        );
+        let (_, errors) = range::parse_collect_errors("<1 || ");
+        assert_eq!(vec![None], errors.iter().map(ParseError::position).collect::<Vec<_>>());
     }
 
     #[test]
-    pub fn test_multiple_05() {
-        let r = range::parse(">=0.5.1-alpha3, <0.6").unwrap();
+    fn test_is_any() {
+        for input in &["*", "", ">=0.0.0", ">=0", ">= 0.0", ">=0.0.0+build", "^1 || >=0.0.0"] {
+            assert!(range::parse(input).unwrap().is_any(), "{}", input);
+        }
 
-        assert_eq!(
-            Predicate {
-                op: Op::GtEq,
-                major: 0,
-                minor: Some(5),
-                patch: Some(1),
-                pre: vec![Identifier::AlphaNumeric(String::from("alpha3"))],
-            },
-            r.predicates[0]
-        );
+        for input in &[">=0.0.1", ">0.0.0", ">=0.0.0-alpha", "^0.0.0", ">=0.0.0, <1.0.0", "1.*"] {
+            assert!(!range::parse(input).unwrap().is_any(), "{}", input);
+        }
+    }
 
-        assert_eq!(
-            Predicate {
-                op: Op::Lt,
-                major: 0,
-                minor: Some(6),
-                patch: None,
-                pre: Vec::new(),
-            },
-            r.predicates[1]
-        );
+    fn boundaries(input: &str) -> Vec<String> {
+        range::parse(input)
+            .unwrap()
+            .boundary_versions()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
     }
 
     #[test]
-    pub fn test_multiple_06() {
-        let r = range::parse("<= 0.2.0 >= 0.5.0").unwrap();
+    fn test_boundary_versions() {
+        let max = u64::MAX;
 
         assert_eq!(
-            Predicate {
-                op: Op::LtEq,
-                major: 0,
-                minor: Some(2),
-                patch: Some(0),
-                pre: Vec::new(),
-            },
-            r.predicates[0]
+            vec![
+                String::from("1.2.2"),
+                String::from("1.2.3"),
+                format!("1.{}.{}", max, max),
+                String::from("2.0.0"),
+            ],
+            boundaries("^1.2.3")
         );
-
+        assert_eq!(vec!["1.2.2", "1.2.3", "1.2.4"], boundaries("=1.2.3 || =1.2.4"));
+        assert_eq!(vec!["1.2.2", "1.2.3"], boundaries("=1.2.3"));
+        assert_eq!(vec!["0.0.0", "0.0.1"], boundaries("^0.0.0"));
         assert_eq!(
-            Predicate {
-                op: Op::GtEq,
-                major: 0,
-                minor: Some(5),
-                patch: Some(0),
-                pre: Vec::new(),
-            },
-            r.predicates[1]
+            vec![format!("0.{}.{}", max, max), String::from("1.0.0"), String::from("1.0.1")],
+            boundaries(">1.0.0, <=1.0.1")
         );
+        assert_eq!(vec!["1.2.2", "1.2.3-alpha"], boundaries(">=1.2.3-alpha"));
+        assert_eq!(vec![format!("0.{}.{}", max, max), String::from("1.0.0")], boundaries("<1"));
+        assert!(boundaries("*").is_empty());
     }
 
     #[test]
-    fn test_parse_build_metadata_with_predicate() {
-        assert_eq!(
-            range::parse("^1.2.3+meta").unwrap().predicates[0].op,
-            Op::Compatible
-        );
-        assert_eq!(
-            range::parse("~1.2.3+meta").unwrap().predicates[0].op,
-            Op::Tilde
-        );
-        assert_eq!(
-            range::parse("=1.2.3+meta").unwrap().predicates[0].op,
-            Op::Ex
-        );
-        assert_eq!(
-            range::parse("<=1.2.3+meta").unwrap().predicates[0].op,
-            Op::LtEq
-        );
-        assert_eq!(
-            range::parse(">=1.2.3+meta").unwrap().predicates[0].op,
-            Op::GtEq
-        );
+    fn test_parse_illegal_character() {
+        use lexer::Error::IllegalCharacter;
+
+        assert_eq!(Err(parser::Error::Lexer(IllegalCharacter('\0', 0))), range::parse("\0"));
         assert_eq!(
-            range::parse("<1.2.3+meta").unwrap().predicates[0].op,
-            Op::Lt
+            Err(parser::Error::Lexer(IllegalCharacter('\u{7f}', 5))),
+            range::parse("^1.2.\u{7f}")
         );
         assert_eq!(
-            range::parse(">1.2.3+meta").unwrap().predicates[0].op,
-            Op::Gt
+            Some(5),
+            ParseError::from(range::parse(">=1.2\0").unwrap_err()).position()
         );
-    }
 
-    #[test]
-    pub fn test_parse_errors() {
-        assert!(range::parse("\0").is_err());
-        assert!(range::parse(">= >= 0.0.2").is_err());
-        assert!(range::parse(">== 0.0.2").is_err());
-        assert!(range::parse("a.0.0").is_err());
-        assert!(range::parse("1.0.0-").is_err());
-        assert!(range::parse(">=").is_err());
-        assert!(range::parse("> 0.1.0,").is_err());
-        assert!(range::parse("> 0.3.0, ,").is_err());
-        assert!(range::parse("> 0. 1").is_err());
+        // a tab is whitespace, separating predicates.
+        assert_eq!(range::parse(">=1.2.3 <2").unwrap(), range::parse(">=1.2.3\t<2").unwrap());
+        let error = range::parse("1.\t2.3").unwrap_err();
+        assert!(!matches!(error, parser::Error::Lexer(IllegalCharacter(..))));
     }
 
-    #[test]
-    pub fn test_large_major_version() {
-        assert!(range::parse("18446744073709551617.0.0").is_err());
+    fn simplified(input: &str) -> String {
+        range::parse(input).unwrap().simplify().to_string()
     }
 
     #[test]
-    pub fn test_large_minor_version() {
-        assert!(range::parse("0.18446744073709551617.0").is_err());
+    fn test_simplify_lower_bounds() {
+        assert_eq!(">=1.2.0", simplified(">=1.0.0, >=1.2.0"));
+        assert_eq!(">=1.2.0", simplified(">=1.2.0, >=1.0.0"));
+        assert_eq!(">1.0.0", simplified(">1.0.0, >=1.0.0"));
+        assert_eq!(">=1.0.0", simplified(">=1.0.0, >=1.0.0"));
+        assert_eq!("^1.2.3", simplified("^1.2.3, >=1.0.0"));
+        assert_eq!(">=1.2.0, <2.0.0", simplified(">=1, >=1.1, >=1.2.0, <2.0.0"));
     }
 
     #[test]
-    pub fn test_large_patch_version() {
-        assert!(range::parse("0.0.18446744073709551617").is_err());
+    fn test_simplify_upper_bounds() {
+        assert_eq!("<=1.5.0", simplified("<2.0.0, <=1.5.0"));
+        assert_eq!("<1.5.0", simplified("<=1.5.0, <1.5.0"));
+        assert_eq!("^1.2.3", simplified("^1.2.3, <3"));
+        assert_eq!("~1.4", simplified("^1.2, ~1.4"));
+        assert_eq!("=1.2.3", simplified("=1.2.3, ^1, <2"));
     }
 
     #[test]
-    pub fn test_op_partialord_lt() {
-        let expect_less = Op::Ex;
-        let other = Op::Gt;
-        assert!(expect_less.lt(&other));
-    }
+    fn test_simplify_unchanged() {
+        for input in &[">=1.0.0, <2.0.0", "^1.2.3", "~1.2, >=1.2.5", "*", ">=1.0.0 || >=1.2.0"] {
+            assert_eq!(range::parse(input).unwrap().to_string(), simplified(input), "{}", input);
+        }
 
-    #[test]
-    pub fn test_op_partialord_le() {
-        let strictly_lt = Op::Ex;
-        let other = Op::Lt;
-        assert!(strictly_lt.le(&other));
-        assert!(other.le(&other));
+        // prereleases decide which prereleases match, so they are kept.
+        assert_eq!(">=1.2.3-alpha, <2.0.0", simplified(">=1.2.3-alpha, >=1.0.0, <2.0.0"));
+        assert_eq!(">=1.2.3-alpha, >=1.2.3", simplified(">=1.2.3-alpha, >=1.2.3"));
     }
 
     #[test]
-    pub fn test_op_partialord_gt() {
-        let expect_gt = Op::Compatible;
-        let other = Op::GtEq;
-        assert!(expect_gt.gt(&other));
-    }
+    fn test_simplify_groups() {
+        assert_eq!(">=1.2.0 || <=0.5.0", simplified(">=1.0.0, >=1.2.0 || <1, <=0.5.0"));
 
-    #[test]
-    pub fn test_op_partialord_ge() {
-        let strictly_gt = Op::Compatible;
-        let other = Op::Tilde;
-        assert!(strictly_gt.ge(&other));
-        assert!(other.ge(&other));
+        let r = range::parse(">=1.0.0, >=1.2.0, <2 || ^3, >=3.1").unwrap();
+        for version in &["1.0.0", "1.2.0", "1.9.0", "2.0.0", "3.0.0", "3.1.0", "4.0.0"] {
+            assert_eq!(r.matches(&v(version)), r.simplify().matches(&v(version)), "{}", version);
+        }
     }
 
     #[test]
-    pub fn test_wildcard_partialord_lt() {
-        let expect_less = WildcardVersion::Minor;
-        let other = WildcardVersion::Patch;
-        assert!(expect_less.lt(&other));
-    }
+    fn test_parse_lines() {
+        let input = "\
+# dependencies
+^1.2.3
 
+  >=2.0.0 <3.0.0   # node style
+>=1.02
+\t
+=1.2.3-alpha+build\r
+# done
+* || 1";
 
-    #[test]
-    pub fn test_wildcard_partialord_le() {
-        let strictly_lt = WildcardVersion::Minor;
-        let other = WildcardVersion::Patch;
-        assert!(strictly_lt.le(&other));
-        assert!(other.le(&other));
+        let results = range::parse_lines(input);
+
+        assert_eq!(
+            vec![2, 4, 5, 7, 9],
+            results.iter().map(|&(line, _)| line).collect::<Vec<_>>()
+        );
+        assert_eq!(range::parse("^1.2.3").ok(), results[0].1.clone().ok());
+        assert_eq!(range::parse(">=2.0.0, <3.0.0").ok(), results[1].1.clone().ok());
+        assert_eq!(range::parse("=1.2.3-alpha+build").ok(), results[3].1.clone().ok());
+        assert_eq!(range::parse("* || 1").ok(), results[4].1.clone().ok());
+
+        let error = results[2].1.clone().unwrap_err();
+        assert_eq!(ParseError::from(range::parse(">=1.02").unwrap_err()), error);
     }
 
     #[test]
-    pub fn test_wildcard_partialord_gt() {
-        let expect_greater = WildcardVersion::Patch;
-        let other = WildcardVersion::Minor;
-        assert!(expect_greater.gt(&other));
+    fn test_parse_lines_empty() {
+        assert!(range::parse_lines("").is_empty());
+        assert!(range::parse_lines("\n  \n# only comments\n").is_empty());
     }
 
     #[test]
-    pub fn test_wildcard_partialord_ge() {
-        let strictly_gt = WildcardVersion::Patch;
-        let other = WildcardVersion::Minor;
-        assert!(strictly_gt.ge(&other));
-        assert!(other.ge(&other));
+    fn test_explicit_op() {
+        let predicate = |input| range::parse_predicate(input).unwrap().unwrap();
+
+        let implicit = predicate("1.2.3");
+        let explicit = predicate("^1.2.3");
+        assert_eq!((Op::Compatible, false), (implicit.op.clone(), implicit.explicit_op()));
+        assert_eq!((Op::Compatible, true), (explicit.op.clone(), explicit.explicit_op()));
+
+        // the flag does not take part in comparisons.
+        assert_eq!(implicit, explicit);
+
+        assert!(predicate("=1.2.3").explicit_op());
+        assert!(predicate(">= 1.2").explicit_op());
+        assert!(predicate("~> 1.2").explicit_op());
+        assert!(predicate("=1.*").explicit_op());
+        assert!(!predicate("1.*").explicit_op());
+        assert!(!predicate("1.2.3-alpha+build").explicit_op());
+        assert!(!Predicate::builder(Op::Tilde, 1).build().explicit_op());
+
+        let r = range::parse("1 || ^2, 2.1").unwrap();
+        assert_eq!(
+            vec![false, true, false],
+            r.iter().map(Predicate::explicit_op).collect::<Vec<_>>()
+        );
     }
 }
@@ -32,8 +32,21 @@
 //! [`Version`]: ./struct.Version.html
 //! [`parse`]: ./fn.parse.html
 
-use std::fmt;
-use parser::{self, Parser};
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Range;
+use core::str::{self, FromStr};
+use lexer;
+use parser::{self, ParseError, Parser};
+use range::VersionReq;
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 /// Structure representing version data.
 ///
@@ -61,7 +74,7 @@ use parser::{self, Parser};
 /// #   try_main().unwrap();
 /// # }
 /// ```
-#[derive(Clone, PartialOrd, Ord, Hash, Debug, PartialEq, Eq)]
+#[derive(Clone, Hash, Debug, PartialEq, Eq)]
 pub struct Version {
     /// Major version as number (`0` in `"0.1.2"`).
     pub major: u64,
@@ -77,6 +90,584 @@ pub struct Version {
     pub build: Vec<Identifier>,
 }
 
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Version) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders versions by semver precedence, see [`compare`], and versions of equal precedence by
+/// their build metadata, so that the order agrees with `==`.
+///
+/// [`compare`]: ./fn.compare.html
+impl Ord for Version {
+    fn cmp(&self, other: &Version) -> Ordering {
+        compare(self, other).then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl Version {
+    /// Construct a release version with the given numeric components, and without prerelease or
+    /// build metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version::{self, Version};
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert_eq!(version::parse("1.2.3")?, Version::new(1, 2, 3));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn new(major: u64, minor: u64, patch: u64) -> Version {
+        Version {
+            major,
+            minor,
+            patch,
+            pre: Vec::new(),
+            build: Vec::new(),
+        }
+    }
+
+    /// Construct a version from its numeric components and the text of its prerelease and build
+    /// metadata, without the leading `-` and `+`.
+    ///
+    /// Empty strings mean no prerelease or build metadata. Error positions are relative to the
+    /// string containing the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version::{self, Version};
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let version = Version::from_parts(1, 2, 3, "alpha.1", "")?;
+    ///
+    /// assert_eq!(version::parse("1.2.3-alpha.1")?, version);
+    /// assert!(Version::from_parts(1, 2, 3, "alpha..1", "").is_err());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn from_parts(
+        major: u64,
+        minor: u64,
+        patch: u64,
+        pre: &str,
+        build: &str,
+    ) -> Result<Version, ParseError> {
+        Ok(Version {
+            major,
+            minor,
+            patch,
+            pre: parse_identifiers(pre, Parser::pre_release)?,
+            build: parse_identifiers(build, Parser::build_metadata)?,
+        })
+    }
+
+    /// Check if this version has the same precedence as another one, which is the case if they
+    /// only differ in build metadata, like `1.2.3+a` and `1.2.3+b`.
+    ///
+    /// Unlike `==`, which also compares the build metadata, this follows the semver rule that
+    /// build metadata does not affect precedence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let a = version::parse("1.2.3+a")?;
+    /// let b = version::parse("1.2.3+b")?;
+    ///
+    /// assert!(a.precedence_eq(&b));
+    /// assert!(a != b);
+    /// assert!(!a.precedence_eq(&version::parse("1.2.3-rc.1+a")?));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn precedence_eq(&self, other: &Version) -> bool {
+        compare(self, other) == Ordering::Equal
+    }
+
+    /// Check if this is a prerelease version, like `1.2.3-alpha1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert!(version::parse("1.2.3-alpha1")?.is_prerelease());
+    /// assert!(!version::parse("1.2.3+build5")?.is_prerelease());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
+    /// Render this version without its build metadata, like `1.2.3-rc.1` for
+    /// `1.2.3-rc.1+build.9`.
+    ///
+    /// This is the same as [`format`] with `omit_build`, without cloning the identifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert_eq!("1.2.3-rc.1", version::parse("1.2.3-rc.1+build.9")?.to_string_no_build());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`format`]: #method.format
+    pub fn to_string_no_build(&self) -> String {
+        use core::fmt::Write;
+
+        let mut out = format!("{}.{}.{}", self.major, self.minor, self.patch);
+
+        for (i, identifier) in self.pre.iter().enumerate() {
+            let separator = if i == 0 { '-' } else { '.' };
+            // writing to a `String` never fails.
+            let _ = write!(out, "{}{}", separator, identifier);
+        }
+
+        out
+    }
+
+    /// Render this version with the given [`FormatOptions`].
+    ///
+    /// With the default options this is the same as the `Display` output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version::{self, FormatOptions};
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let version = version::parse("1.2.3-rc.1+build.9")?;
+    /// let options = FormatOptions {
+    ///     leading_v: true,
+    ///     omit_build: true,
+    ///     ..FormatOptions::default()
+    /// };
+    ///
+    /// assert_eq!("v1.2.3-rc.1", version.format(options));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`FormatOptions`]: ./struct.FormatOptions.html
+    pub fn format(&self, options: FormatOptions) -> String {
+        let mut version = Version::new(self.major, self.minor, self.patch);
+
+        if !options.omit_pre {
+            version.pre = self.pre.clone();
+        }
+
+        if !options.omit_build {
+            version.build = self.build.clone();
+        }
+
+        let mut out = String::new();
+
+        if options.leading_v {
+            out.push('v');
+        }
+
+        out.push_str(&version.to_string());
+        out
+    }
+
+    /// Check if this is exactly `0.0.0`, without prerelease or build metadata.
+    ///
+    /// Some tools use it as a placeholder for unpublished packages. Note that `^0.0.0` only
+    /// matches `0.0.0` itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert!(version::parse("0.0.0")?.is_zero());
+    /// assert!(!version::parse("0.0.0-alpha")?.is_zero());
+    /// assert!(!version::parse("0.0.1")?.is_zero());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn is_zero(&self) -> bool {
+        self.major == 0 && self.minor == 0 && self.patch == 0 && self.pre.is_empty() &&
+            self.build.is_empty()
+    }
+
+    /// The `major`, `minor` and `patch` components as a tuple, handy as a sort key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// assert_eq!((1, 2, 3), version::parse("1.2.3-alpha1+build5")?.major_minor_patch());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn major_minor_patch(&self) -> (u64, u64, u64) {
+        (self.major, self.minor, self.patch)
+    }
+
+    /// Check if this version matches the given [`range::VersionReq`].
+    ///
+    /// This is the same as [`VersionReq::matches`], asked from the side of the version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::{range, version};
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let r = range::parse("^1.2")?;
+    /// let releases = vec![version::parse("1.1.0")?, version::parse("1.4.2")?];
+    ///
+    /// let matching: Vec<_> = releases.into_iter().filter(|v| v.satisfies(&r)).collect();
+    /// assert_eq!(vec![version::parse("1.4.2")?], matching);
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`range::VersionReq`]: ../range/struct.VersionReq.html
+    /// [`VersionReq::matches`]: ../range/struct.VersionReq.html#method.matches
+    pub fn satisfies(&self, req: &VersionReq) -> bool {
+        req.matches(self)
+    }
+
+    /// Check if this version belongs to the given release series, like `1.*` or `1.2.*`.
+    ///
+    /// The major version has to be the same, and the minor version as well if it is given.
+    /// Unlike with a [`VersionReq`], prereleases are part of their series.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let version = version::parse("1.2.3-alpha1")?;
+    ///
+    /// assert!(version.in_series(1, Some(2)));
+    /// assert!(version.in_series(1, None));
+    /// assert!(!version.in_series(1, Some(3)));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`VersionReq`]: ../range/struct.VersionReq.html
+    pub fn in_series(&self, major: u64, minor: Option<u64>) -> bool {
+        match minor {
+            Some(minor) => self.major == major && self.minor == minor,
+            None => self.major == major,
+        }
+    }
+
+    /// Check if this version is compatible with another one under the caret rules cargo uses.
+    ///
+    /// Versions are compatible if they have the same major version, or for `0.y.z` versions the
+    /// same minor version, or for `0.0.z` versions the same patch version. This is symmetric and
+    /// does not tell which version is newer, use [`satisfies`] with a caret requirement for that.
+    /// Prerelease and build metadata are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let version = version::parse("1.2.0")?;
+    ///
+    /// assert!(version.is_compatible_with(&version::parse("1.5.0")?));
+    /// assert!(!version.is_compatible_with(&version::parse("2.0.0")?));
+    /// assert!(!version::parse("0.2.0")?.is_compatible_with(&version::parse("0.3.0")?));
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`satisfies`]: #method.satisfies
+    pub fn is_compatible_with(&self, other: &Version) -> bool {
+        if self.major != other.major {
+            return false;
+        }
+
+        match self.major {
+            0 if self.minor == 0 => other.minor == 0 && self.patch == other.patch,
+            0 => self.minor == other.minor,
+            _ => true,
+        }
+    }
+
+    /// Find the highest component in which this version differs from another one, or `None` if
+    /// they have the same precedence.
+    ///
+    /// This is symmetric. Build metadata is ignored like in [`precedence_eq`] but unlike with `==`,
+    /// so `diff` returns `None` exactly when `precedence_eq` returns `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version::{self, VersionDiff};
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let version = version::parse("1.2.3")?;
+    ///
+    /// assert_eq!(Some(VersionDiff::Minor), version.diff(&version::parse("1.3.3")?));
+    /// assert_eq!(Some(VersionDiff::Pre), version.diff(&version::parse("1.2.3-rc.1")?));
+    /// assert_eq!(None, version.diff(&version::parse("1.2.3+build")?));
+    /// assert!(version != version::parse("1.2.3+build")?);
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`precedence_eq`]: #method.precedence_eq
+    pub fn diff(&self, other: &Version) -> Option<VersionDiff> {
+        if self.major != other.major {
+            Some(VersionDiff::Major)
+        } else if self.minor != other.minor {
+            Some(VersionDiff::Minor)
+        } else if self.patch != other.patch {
+            Some(VersionDiff::Patch)
+        } else if self.pre != other.pre {
+            Some(VersionDiff::Pre)
+        } else {
+            None
+        }
+    }
+
+    /// Increment the major version, resetting the minor and patch versions to zero.
+    ///
+    /// Clears the pre-release and build metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let mut version = version::parse("1.2.3-alpha1+build5")?;
+    /// version.increment_major();
+    ///
+    /// assert_eq!(version::parse("2.0.0")?, version);
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn increment_major(&mut self) {
+        self.major += 1;
+        self.minor = 0;
+        self.patch = 0;
+        self.pre = Vec::new();
+        self.build = Vec::new();
+    }
+
+    /// Increment the minor version, resetting the patch version to zero.
+    ///
+    /// Clears the pre-release and build metadata.
+    pub fn increment_minor(&mut self) {
+        self.minor += 1;
+        self.patch = 0;
+        self.pre = Vec::new();
+        self.build = Vec::new();
+    }
+
+    /// Increment the patch version.
+    ///
+    /// Clears the pre-release and build metadata.
+    pub fn increment_patch(&mut self) {
+        self.patch += 1;
+        self.pre = Vec::new();
+        self.build = Vec::new();
+    }
+
+    /// Increment the given [`Level`] of the version, like [`increment_major`],
+    /// [`increment_minor`] or [`increment_patch`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version::{self, Level};
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let mut version = version::parse("1.2.3-alpha1+build5")?;
+    /// let level: Level = "minor".parse()?;
+    /// version.bump(level);
+    ///
+    /// assert_eq!(version::parse("1.3.0")?, version);
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`Level`]: ./enum.Level.html
+    /// [`increment_major`]: #method.increment_major
+    /// [`increment_minor`]: #method.increment_minor
+    /// [`increment_patch`]: #method.increment_patch
+    pub fn bump(&mut self, level: Level) {
+        match level {
+            Level::Major => self.increment_major(),
+            Level::Minor => self.increment_minor(),
+            Level::Patch => self.increment_patch(),
+        }
+    }
+
+    /// The version with the major version incremented, like [`increment_major`], or `None` if
+    /// the major version is already `u64::MAX`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version::{self, Version};
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let version = version::parse("1.2.3-alpha1")?;
+    ///
+    /// assert_eq!(Some(Version::new(2, 0, 0)), version.checked_increment_major());
+    /// assert_eq!(None, Version::new(u64::MAX, 0, 0).checked_increment_major());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    /// [`increment_major`]: #method.increment_major
+    pub fn checked_increment_major(&self) -> Option<Version> {
+        Some(Version::new(self.major.checked_add(1)?, 0, 0))
+    }
+
+    /// The version with the minor version incremented, like [`increment_minor`], or `None` if
+    /// the minor version is already `u64::MAX`.
+    ///
+    /// [`increment_minor`]: #method.increment_minor
+    pub fn checked_increment_minor(&self) -> Option<Version> {
+        Some(Version::new(self.major, self.minor.checked_add(1)?, 0))
+    }
+
+    /// The version with the patch version incremented, like [`increment_patch`], or `None` if
+    /// the patch version is already `u64::MAX`.
+    ///
+    /// [`increment_patch`]: #method.increment_patch
+    pub fn checked_increment_patch(&self) -> Option<Version> {
+        Some(Version::new(self.major, self.minor, self.patch.checked_add(1)?))
+    }
+
+    /// Remove the pre-release metadata, turning this into the corresponding release.
+    pub fn clear_pre(&mut self) {
+        self.pre = Vec::new();
+    }
+
+    /// Remove the build metadata.
+    pub fn clear_build(&mut self) {
+        self.build = Vec::new();
+    }
+
+    /// A copy of this version without pre-release metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let version = version::parse("1.2.3-alpha1+build5")?;
+    ///
+    /// assert_eq!("1.2.3+build5", version.without_pre().to_string());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn without_pre(&self) -> Version {
+        let mut version = self.clone();
+        version.clear_pre();
+        version
+    }
+
+    /// A copy of this version without build metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version;
+    ///
+    /// # fn try_main() -> Result<(), String> {
+    /// let version = version::parse("1.2.3-alpha1+build5")?;
+    ///
+    /// assert_eq!("1.2.3-alpha1", version.without_build().to_string());
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #   try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn without_build(&self) -> Version {
+        let mut version = self.clone();
+        version.clear_build();
+        version
+    }
+}
+
 /// Helper enum for holding data of alphanumeric or numeric suffix identifiers.
 ///
 /// This enum is used to hold suffix parts of `pre` and `build` fields of
@@ -106,7 +697,8 @@ pub struct Version {
 /// # }
 /// ```
 /// [`Version`]: ./struct.Version.html
-#[derive(Clone, PartialOrd, Ord, Hash, Debug, PartialEq, Eq)]
+#[derive(Clone, Hash, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Identifier {
     /// An identifier that's solely numbers.
     Numeric(u64),
@@ -114,10 +706,399 @@ pub enum Identifier {
     AlphaNumeric(String),
 }
 
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Identifier) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders identifiers by semver precedence: numeric identifiers compare numerically, so `2` is
+/// lower than `10`, alphanumeric ones compare lexically in ASCII order, and numeric identifiers
+/// are always lower than alphanumeric ones.
+impl Ord for Identifier {
+    fn cmp(&self, other: &Identifier) -> Ordering {
+        match (self, other) {
+            (Identifier::Numeric(a), Identifier::Numeric(b)) => a.cmp(b),
+            (Identifier::Numeric(_), Identifier::AlphaNumeric(_)) => Ordering::Less,
+            (Identifier::AlphaNumeric(_), Identifier::Numeric(_)) => Ordering::Greater,
+            (Identifier::AlphaNumeric(a), Identifier::AlphaNumeric(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl Identifier {
+    /// Check if this is a numeric identifier, like `7` in `1.2.3-alpha.7`.
+    pub fn is_numeric(&self) -> bool {
+        match *self {
+            Identifier::Numeric(_) => true,
+            Identifier::AlphaNumeric(_) => false,
+        }
+    }
+
+    /// The value of a numeric identifier, or `None` if it is alphanumeric.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version::Identifier;
+    ///
+    /// assert_eq!(Some(7), Identifier::Numeric(7).as_u64());
+    /// assert_eq!(None, Identifier::AlphaNumeric(String::from("alpha")).as_u64());
+    /// ```
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Identifier::Numeric(n) => Some(n),
+            Identifier::AlphaNumeric(_) => None,
+        }
+    }
+
+    /// The text of an alphanumeric identifier, or `None` if it is numeric.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use semver_parser::version::Identifier;
+    ///
+    /// assert_eq!(Some("alpha"), Identifier::AlphaNumeric(String::from("alpha")).as_str());
+    /// assert_eq!(None, Identifier::Numeric(7).as_str());
+    /// ```
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Identifier::Numeric(_) => None,
+            Identifier::AlphaNumeric(ref s) => Some(s),
+        }
+    }
+}
+
+/// A [`Version`] compared, hashed and ordered by semver precedence only, for use as a map key.
+///
+/// Versions which only differ in build metadata, like `1.2.3+a` and `1.2.3+b`, are the same key,
+/// while `Version` itself compares and hashes the build metadata too.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::version::{self, PrecedenceVersion};
+/// use std::collections::HashSet;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let mut set = HashSet::new();
+/// set.insert(PrecedenceVersion(version::parse("1.2.3+a")?));
+/// set.insert(PrecedenceVersion(version::parse("1.2.3+b")?));
+/// assert_eq!(1, set.len());
+///
+/// let versions: HashSet<_> = vec![version::parse("1.2.3+a")?, version::parse("1.2.3+b")?]
+///     .into_iter()
+///     .collect();
+/// assert_eq!(2, versions.len());
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`Version`]: ./struct.Version.html
+#[derive(Clone, Debug)]
+pub struct PrecedenceVersion(pub Version);
+
+impl PartialEq for PrecedenceVersion {
+    fn eq(&self, other: &PrecedenceVersion) -> bool {
+        self.0.precedence_eq(&other.0)
+    }
+}
+
+impl Eq for PrecedenceVersion {}
+
+impl Hash for PrecedenceVersion {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.major.hash(state);
+        self.0.minor.hash(state);
+        self.0.patch.hash(state);
+        self.0.pre.hash(state);
+    }
+}
+
+impl PartialOrd for PrecedenceVersion {
+    fn partial_cmp(&self, other: &PrecedenceVersion) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PrecedenceVersion {
+    fn cmp(&self, other: &PrecedenceVersion) -> Ordering {
+        compare(&self.0, &other.0)
+    }
+}
+
+impl From<Version> for PrecedenceVersion {
+    fn from(version: Version) -> PrecedenceVersion {
+        PrecedenceVersion(version)
+    }
+}
+
+/// A component of a [`Version`] to increment with [`Version::bump`].
+///
+/// [`Version`]: ./struct.Version.html
+/// [`Version::bump`]: ./struct.Version.html#method.bump
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Level {
+    /// The major version, `X` in `X.y.z`.
+    Major,
+    /// The minor version, `Y` in `x.Y.z`.
+    Minor,
+    /// The patch version, `Z` in `x.y.Z`.
+    Patch,
+}
+
+impl Level {
+    /// The name of this level, like `"major"` for [`Level::Major`].
+    ///
+    /// [`Level::Major`]: #variant.Major
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Level::Major => "major",
+            Level::Minor => "minor",
+            Level::Patch => "patch",
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Level, String> {
+        match s {
+            "major" => Ok(Level::Major),
+            "minor" => Ok(Level::Minor),
+            "patch" => Ok(Level::Patch),
+            _ => Err(String::from("Could not parse Level")),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The highest component in which two versions differ, as returned by [`Version::diff`].
+///
+/// [`Version::diff`]: ./struct.Version.html#method.diff
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VersionDiff {
+    /// The major versions differ.
+    Major,
+    /// The minor versions differ, the major versions are the same.
+    Minor,
+    /// The patch versions differ, the major and minor versions are the same.
+    Patch,
+    /// Only the prerelease identifiers differ.
+    Pre,
+}
+
+impl VersionDiff {
+    /// The name of this difference, like `"major"` for [`VersionDiff::Major`].
+    ///
+    /// [`VersionDiff::Major`]: #variant.Major
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            VersionDiff::Major => "major",
+            VersionDiff::Minor => "minor",
+            VersionDiff::Patch => "patch",
+            VersionDiff::Pre => "pre",
+        }
+    }
+}
+
+impl fmt::Display for VersionDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Options for rendering a [`Version`] with [`Version::format`].
+///
+/// The defaults render the version like its `Display` implementation.
+///
+/// [`Version`]: ./struct.Version.html
+/// [`Version::format`]: ./struct.Version.html#method.format
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FormatOptions {
+    /// Prefix the version with a `v`, like in git tags, `false` by default.
+    pub leading_v: bool,
+    /// Leave out the prerelease, `false` by default.
+    pub omit_pre: bool,
+    /// Leave out the build metadata, `false` by default.
+    pub omit_build: bool,
+}
+
+/// Byte ranges of the components of a version in the input it was parsed from, as returned by
+/// [`parse_spanned`].
+///
+/// The ranges do not include the separators, so in `1.2.3-alpha.1` the prerelease spans `6..13`.
+///
+/// [`parse_spanned`]: ./fn.parse_spanned.html
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VersionSpans {
+    /// Span of the major version.
+    pub major: Range<usize>,
+    /// Span of the minor version.
+    pub minor: Range<usize>,
+    /// Span of the patch version.
+    pub patch: Range<usize>,
+    /// Span of the prerelease identifiers, or `None` if there is no prerelease.
+    pub pre: Option<Range<usize>>,
+    /// Span of the build metadata identifiers, or `None` if there is no build metadata.
+    pub build: Option<Range<usize>>,
+}
+
+/// Cache of alphanumeric identifiers, shared between the versions parsed with
+/// [`parse_interned`].
+///
+/// Common prerelease tags like `alpha` or `rc` are only allocated once per interner, instead of
+/// once per parsed version.
+///
+/// [`parse_interned`]: ./fn.parse_interned.html
+#[derive(Clone, Debug, Default)]
+pub struct Interner {
+    identifiers: BTreeSet<Arc<str>>,
+}
+
+impl Interner {
+    /// Construct an empty interner.
+    pub fn new() -> Interner {
+        Interner::default()
+    }
+
+    /// Get the shared copy of the given identifier, allocating it on first use.
+    pub fn intern(&mut self, identifier: &str) -> Arc<str> {
+        if let Some(shared) = self.identifiers.get(identifier) {
+            return shared.clone();
+        }
+
+        let shared: Arc<str> = Arc::from(identifier);
+        self.identifiers.insert(shared.clone());
+        shared
+    }
+
+    /// The number of distinct identifiers in this interner.
+    pub fn len(&self) -> usize {
+        self.identifiers.len()
+    }
+
+    /// Check if this interner holds no identifiers.
+    pub fn is_empty(&self) -> bool {
+        self.identifiers.is_empty()
+    }
+}
+
+/// Like [`Identifier`], but alphanumeric identifiers are shared through an [`Interner`].
+///
+/// [`Identifier`]: ./enum.Identifier.html
+/// [`Interner`]: ./struct.Interner.html
+#[derive(Clone, PartialOrd, Ord, Hash, Debug, PartialEq, Eq)]
+pub enum InternedIdentifier {
+    /// An identifier that's solely numbers.
+    Numeric(u64),
+    /// An identifier with letters and numbers.
+    AlphaNumeric(Arc<str>),
+}
+
+impl<'a> From<&'a InternedIdentifier> for Identifier {
+    fn from(value: &'a InternedIdentifier) -> Self {
+        match *value {
+            InternedIdentifier::Numeric(n) => Identifier::Numeric(n),
+            InternedIdentifier::AlphaNumeric(ref s) => Identifier::AlphaNumeric(s.to_string()),
+        }
+    }
+}
+
+impl PartialEq<Identifier> for InternedIdentifier {
+    fn eq(&self, other: &Identifier) -> bool {
+        match (self, other) {
+            (InternedIdentifier::Numeric(a), Identifier::Numeric(b)) => a == b,
+            (InternedIdentifier::AlphaNumeric(a), Identifier::AlphaNumeric(b)) => **a == **b,
+            _ => false,
+        }
+    }
+}
+
+/// Like [`Version`], but with identifiers shared through an [`Interner`], as returned by
+/// [`parse_interned`].
+///
+/// [`Version`]: ./struct.Version.html
+/// [`Interner`]: ./struct.Interner.html
+/// [`parse_interned`]: ./fn.parse_interned.html
+#[derive(Clone, Debug)]
+pub struct InternedVersion {
+    /// Major version as number.
+    pub major: u64,
+    /// Minor version as number.
+    pub minor: u64,
+    /// Patch version as number.
+    pub patch: u64,
+    /// Pre-release metadata.
+    pub pre: Vec<InternedIdentifier>,
+    /// Build metadata.
+    pub build: Vec<InternedIdentifier>,
+}
+
+impl<'a> From<&'a InternedVersion> for Version {
+    fn from(value: &'a InternedVersion) -> Self {
+        Version {
+            major: value.major,
+            minor: value.minor,
+            patch: value.patch,
+            pre: value.pre.iter().map(Identifier::from).collect(),
+            build: value.build.iter().map(Identifier::from).collect(),
+        }
+    }
+}
+
+/// Compares like two [`Version`]s, including the build metadata.
+///
+/// [`Version`]: ./struct.Version.html
+impl PartialEq<Version> for InternedVersion {
+    fn eq(&self, other: &Version) -> bool {
+        self.major == other.major && self.minor == other.minor && self.patch == other.patch &&
+            self.pre == other.pre && self.build == other.build
+    }
+}
+
+/// Parse all of the input with the given identifier parser, an empty input has no identifiers.
+fn parse_identifiers<'input, F>(
+    input: &'input str,
+    parse: F,
+) -> Result<Vec<Identifier>, ParseError>
+where
+    F: FnOnce(&mut Parser<'input>) -> Result<Vec<Identifier>, parser::Error<'input>>,
+{
+    if input.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut parser = Parser::new(input)?;
+    let identifiers = parse(&mut parser)?;
+
+    if !parser.is_eof() {
+        let position = parser.position();
+        return Err(parser::Error::MoreInput(parser.tail()?, position).into());
+    }
+
+    Ok(identifiers)
+}
+
 /// Function for parsing version string to [`Version`].
 ///
 /// Returns `Result<`[`Version`]`, String>`, where `String` represents an error while parsing.
 ///
+/// All three components are required, so partial versions like `0-Foo` are rejected even though
+/// they are valid range predicates. Use [`parse_partial`] to accept those.
+///
 /// # Examples
 ///
 /// Parsing [`Version`] from string and checking its fields:
@@ -140,356 +1121,1834 @@ pub enum Identifier {
 /// # }
 /// ```
 /// [`Version`]: ./struct.Version.html
+/// [`parse_partial`]: ./fn.parse_partial.html
 pub fn parse<'input>(input: &'input str) -> Result<Version, parser::Error<'input>> {
     let mut parser = Parser::new(input)?;
     let version = parser.version()?;
 
     if !parser.is_eof() {
-        return Err(parser::Error::MoreInput(parser.tail()?));
+        let position = parser.position();
+        return Err(parser::Error::MoreInput(parser.tail()?, position));
     }
 
     Ok(version)
 }
 
-impl fmt::Display for Version {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        try!(write!(f, "{}.{}.{}", self.major, self.minor, self.patch));
-        if !self.pre.is_empty() {
-            let strs: Vec<_> = self.pre.iter().map(ToString::to_string).collect();
-            try!(write!(f, "-{}", strs.join(".")));
-        }
-        if !self.build.is_empty() {
-            let strs: Vec<_> = self.build.iter().map(ToString::to_string).collect();
-            try!(write!(f, "+{}", strs.join(".")));
-        }
-        Ok(())
-    }
+/// Check if the input is a valid version, accepted by [`parse`].
+///
+/// This does not build the [`Version`], so it does not allocate for prerelease and build
+/// identifiers.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::version;
+///
+/// assert!(version::is_valid("1.2.3-alpha.1+build.5"));
+/// assert!(!version::is_valid("1.2"));
+/// assert!(!version::is_valid("1.2.3-01"));
+/// ```
+/// [`parse`]: ./fn.parse.html
+/// [`Version`]: ./struct.Version.html
+pub fn is_valid(input: &str) -> bool {
+    let mut parser = match Parser::new(input) {
+        Ok(parser) => parser,
+        Err(_) => return false,
+    };
+
+    parser.validate_version().is_ok() && parser.is_eof()
 }
 
-impl fmt::Display for Identifier {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            Identifier::Numeric(ref id) => id.fmt(f),
-            Identifier::AlphaNumeric(ref id) => id.fmt(f),
+/// Function for parsing a [`Version`] from bytes.
+///
+/// Works like [`parse`] after checking that the input is valid UTF-8. Invalid UTF-8 is reported
+/// as an unexpected character at the offset of the first invalid byte.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::version;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let version = version::parse_bytes(b"1.2.3")?;
+/// assert_eq!(version::parse("1.2.3")?, version);
+///
+/// assert!(version::parse_bytes(b"1.2.\xff").is_err());
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`Version`]: ./struct.Version.html
+/// [`parse`]: ./fn.parse.html
+pub fn parse_bytes(input: &[u8]) -> Result<Version, parser::Error<'_>> {
+    parse(str::from_utf8(input)?)
+}
+
+/// Function for parsing a version string to a [`Version`], along with the [`VersionSpans`] of
+/// its components.
+///
+/// Works like [`parse`], and is meant for tooling which highlights parts of the input.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::version;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let input = "1.2.3-alpha.1+build";
+/// let (version, spans) = version::parse_spanned(input)?;
+///
+/// assert_eq!(version::parse(input)?, version);
+/// assert_eq!(Some(6..13), spans.pre);
+/// assert_eq!("build", &input[spans.build.unwrap()]);
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`Version`]: ./struct.Version.html
+/// [`VersionSpans`]: ./struct.VersionSpans.html
+/// [`parse`]: ./fn.parse.html
+pub fn parse_spanned<'input>(
+    input: &'input str,
+) -> Result<(Version, VersionSpans), parser::Error<'input>> {
+    let mut parser = Parser::new(input)?;
+    let result = parser.spanned_version()?;
+
+    if !parser.is_eof() {
+        let position = parser.position();
+        return Err(parser::Error::MoreInput(parser.tail()?, position));
+    }
+
+    Ok(result)
+}
+
+/// Function for parsing a version string to an [`InternedVersion`].
+///
+/// Works like [`parse`], but alphanumeric identifiers are shared through the given
+/// [`Interner`], which saves allocations when parsing many versions with the same prerelease
+/// tags.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::version::{self, Interner};
+///
+/// # fn try_main() -> Result<(), String> {
+/// let mut interner = Interner::new();
+/// let a = version::parse_interned("1.0.0-beta.1", &mut interner)?;
+/// let b = version::parse_interned("2.0.0-beta.2", &mut interner)?;
+///
+/// assert_eq!(a, version::parse("1.0.0-beta.1")?);
+/// assert_eq!(b, version::parse("2.0.0-beta.2")?);
+/// assert_eq!(1, interner.len());
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`InternedVersion`]: ./struct.InternedVersion.html
+/// [`Interner`]: ./struct.Interner.html
+/// [`parse`]: ./fn.parse.html
+pub fn parse_interned<'input>(
+    input: &'input str,
+    interner: &mut Interner,
+) -> Result<InternedVersion, parser::Error<'input>> {
+    let mut parser = Parser::new(input)?;
+    let version = parser.interned_version(interner)?;
+
+    if !parser.is_eof() {
+        let position = parser.position();
+        return Err(parser::Error::MoreInput(parser.tail()?, position));
+    }
+
+    Ok(version)
+}
+
+/// Function for leniently parsing version string to [`Version`].
+///
+/// Works like [`parse`], but also accepts a single leading `v` or `V`, as commonly found in git
+/// tags, and surrounding whitespace. Byte offsets in errors are relative to the input after both
+/// the surrounding whitespace and the prefix have been stripped, not to the original input, so
+/// the error for `"  v1.2.3 a"` points at offset `6` of `"1.2.3 a"`.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::version;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let version = version::parse_lenient(" v1.2.3 ")?;
+/// assert_eq!(version::parse("1.2.3")?, version);
+///
+/// assert!(version::parse("v1.2.3").is_err());
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`Version`]: ./struct.Version.html
+/// [`parse`]: ./fn.parse.html
+pub fn parse_lenient<'input>(input: &'input str) -> Result<Version, parser::Error<'input>> {
+    let input = input.trim();
+
+    if input.starts_with('v') || input.starts_with('V') {
+        parse(&input[1..])
+    } else {
+        parse(input)
+    }
+}
+
+/// Function for parsing a version string to [`Version`], accepting any characters in the build
+/// metadata.
+///
+/// Works like [`parse`], but everything after the `+` up to the next whitespace is returned as
+/// the opaque text of the build metadata next to the version, so build metadata like `+build/123`
+/// from CI systems is accepted. Only whitespace may follow the build metadata. The returned
+/// version never has build metadata itself, since it would not be valid semver; the text is
+/// empty if there is no build metadata.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::version;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let (version, build) = version::parse_tolerant_build("1.2.3+build/123")?;
+/// assert_eq!(version::parse("1.2.3")?, version);
+/// assert_eq!("build/123", build);
+///
+/// assert!(version::parse("1.2.3+build/123").is_err());
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`Version`]: ./struct.Version.html
+/// [`parse`]: ./fn.parse.html
+pub fn parse_tolerant_build<'input>(
+    input: &'input str,
+) -> Result<(Version, &'input str), parser::Error<'input>> {
+    let plus = match input.find('+') {
+        // the version itself has to be right before the build metadata.
+        Some(plus) if !input[..plus].ends_with(char::is_whitespace) => plus,
+        _ => return parse(input).map(|version| (version, "")),
+    };
+
+    let version = parse(&input[..plus])?;
+
+    let rest = &input[plus + 1..];
+    let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+    let build = &rest[..end];
+
+    // let the strict parser report empty build metadata.
+    if build.is_empty() {
+        return parse(input).map(|version| (version, ""));
+    }
+
+    if let Some((offset, c)) = build.char_indices().find(|&(_, c)| c.is_control()) {
+        let position = plus + 1 + offset;
+        return Err(parser::Error::Lexer(lexer::Error::IllegalCharacter(c, position)));
+    }
+
+    let trailing = rest[end..].char_indices().find(|&(_, c)| !c.is_whitespace());
+
+    if let Some((offset, c)) = trailing {
+        let position = plus + 1 + end + offset;
+        let error = if c.is_control() {
+            lexer::Error::IllegalCharacter(c, position)
+        } else {
+            lexer::Error::UnexpectedChar(c, position)
+        };
+
+        return Err(parser::Error::Lexer(error));
+    }
+
+    Ok((version, build))
+}
+
+/// Function for parsing a possibly incomplete version string to [`Version`].
+///
+/// Unlike [`parse`], the minor and patch components may be left out, in which case they default
+/// to zero. This is useful for ecosystems which write `1.2` or `1` as a full version.
+///
+/// A prerelease applies to the version with the missing components filled in, so `0-Foo` is
+/// `0.0.0-Foo`. This is also the prerelease a range predicate like `0-Foo` allows.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::version;
+///
+/// # fn try_main() -> Result<(), String> {
+/// assert_eq!(version::parse("1.0.0")?, version::parse_partial("1")?);
+/// assert_eq!(version::parse("1.2.0-rc.1")?, version::parse_partial("1.2-rc.1")?);
+///
+/// assert!(version::parse("1.2").is_err());
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`Version`]: ./struct.Version.html
+/// [`parse`]: ./fn.parse.html
+pub fn parse_partial<'input>(input: &'input str) -> Result<Version, parser::Error<'input>> {
+    let mut parser = Parser::new(input)?;
+    let version = parser.partial_version()?;
+
+    if !parser.is_eof() {
+        let position = parser.position();
+        return Err(parser::Error::MoreInput(parser.tail()?, position));
+    }
+
+    Ok(version)
+}
+
+/// Function for parsing version string to [`Version`], accepting components which do not fit in
+/// `u64`.
+///
+/// Such components are saturated at `u64::MAX` instead of failing the parse, and the returned
+/// flag is `true` if that happened, so that the caller can decide what to do with the version.
+/// Otherwise this behaves like [`parse`].
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::version;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let (version, overflowed) = version::parse_saturating("18446744073709551617.0.0")?;
+///
+/// assert_eq!(u64::MAX, version.major);
+/// assert!(overflowed);
+///
+/// assert!(version::parse("18446744073709551617.0.0").is_err());
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`Version`]: ./struct.Version.html
+/// [`parse`]: ./fn.parse.html
+pub fn parse_saturating<'input>(
+    input: &'input str,
+) -> Result<(Version, bool), parser::Error<'input>> {
+    let mut parser = Parser::new(input)?;
+    let version = parser.saturating_version()?;
+
+    if !parser.is_eof() {
+        let position = parser.position();
+        return Err(parser::Error::MoreInput(parser.tail()?, position));
+    }
+
+    Ok(version)
+}
+
+/// Compare two versions by semver precedence.
+///
+/// Numeric components are compared first. A version with a prerelease is lower than the same
+/// version without one, and prereleases are compared identifier by identifier: numeric
+/// identifiers compare numerically and rank below alphanumeric ones, which compare lexically.
+/// Build metadata is ignored.
+///
+/// [`Version`] implements `Ord` like this, but breaks ties by comparing build metadata so that the
+/// order agrees with `==`.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::version;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let mut versions = vec![
+///     version::parse("1.0.0")?,
+///     version::parse("1.0.0-beta.11")?,
+///     version::parse("1.0.0-beta.2")?,
+/// ];
+/// versions.sort_by(version::compare);
+///
+/// let sorted: Vec<_> = versions.iter().map(ToString::to_string).collect();
+/// assert_eq!(vec!["1.0.0-beta.2", "1.0.0-beta.11", "1.0.0"], sorted);
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`Version`]: ./struct.Version.html
+pub fn compare(a: &Version, b: &Version) -> Ordering {
+    compare_by(a, b, Identifier::cmp)
+}
+
+/// Compare two versions like [`compare`], but ignoring the case of alphanumeric prerelease
+/// identifiers.
+///
+/// Semver identifiers are case-sensitive, so this is not spec-compliant, but some ecosystems treat
+/// `1.0.0-Alpha` and `1.0.0-alpha` as the same version.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::version;
+/// use std::cmp::Ordering;
+///
+/// # fn try_main() -> Result<(), String> {
+/// let a = version::parse("1.0.0-Alpha")?;
+/// let b = version::parse("1.0.0-alpha")?;
+///
+/// assert_eq!(Ordering::Less, version::compare(&a, &b));
+/// assert_eq!(Ordering::Equal, version::compare_ignore_pre_case(&a, &b));
+/// # Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #   try_main().unwrap();
+/// # }
+/// ```
+/// [`compare`]: ./fn.compare.html
+pub fn compare_ignore_pre_case(a: &Version, b: &Version) -> Ordering {
+    compare_by(a, b, |a, b| match (a.as_str(), b.as_str()) {
+        (Some(a), Some(b)) => {
+            let a = a.bytes().map(|c| c.to_ascii_lowercase());
+            let b = b.bytes().map(|c| c.to_ascii_lowercase());
+            a.cmp(b)
+        }
+        _ => a.cmp(b),
+    })
+}
+
+/// Compare two versions by semver precedence, using the given comparison for prerelease
+/// identifiers.
+fn compare_by<F>(a: &Version, b: &Version, cmp_identifier: F) -> Ordering
+where
+    F: Fn(&Identifier, &Identifier) -> Ordering,
+{
+    a.major
+        .cmp(&b.major)
+        .then_with(|| a.minor.cmp(&b.minor))
+        .then_with(|| a.patch.cmp(&b.patch))
+        .then_with(|| match (a.pre.is_empty(), b.pre.is_empty()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => {
+                a.pre
+                    .iter()
+                    .zip(&b.pre)
+                    .map(|(a, b)| cmp_identifier(a, b))
+                    .find(|&ordering| ordering != Ordering::Equal)
+                    .unwrap_or_else(|| a.pre.len().cmp(&b.pre.len()))
+            }
+        })
+}
+
+/// Parse every whitespace-separated version in the input.
+///
+/// Each entry is parsed on its own, so a malformed entry yields an error without affecting the
+/// others. Positions in the errors are relative to the start of their entry.
+///
+/// # Examples
+///
+/// ```
+/// use semver_parser::version;
+///
+/// let results: Vec<_> = version::parse_many("1.0.0\n2.x.0 3.1.4").collect();
+///
+/// assert_eq!(3, results.len());
+/// assert!(results[0].is_ok());
+/// assert!(results[1].is_err());
+/// assert_eq!(version::parse("3.1.4").ok(), results[2].clone().ok());
+/// ```
+pub fn parse_many(input: &str) -> impl Iterator<Item = Result<Version, ParseError>> + '_ {
+    input.split_whitespace().map(|entry| entry.parse())
+}
+
+impl FromStr for Version {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Version, ParseError> {
+        parse(s).map_err(ParseError::from)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Version {
+    type Error = ParseError;
+
+    fn try_from(s: &'a str) -> Result<Version, ParseError> {
+        s.parse()
+    }
+}
+
+/// Formats the version in its canonical form, like `1.2.3-alpha.1+build.5`.
+///
+/// The output parses back into an equal [`Version`] through [`parse`].
+///
+/// [`Version`]: ./struct.Version.html
+/// [`parse`]: ./fn.parse.html
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            let strs: Vec<_> = self.pre.iter().map(ToString::to_string).collect();
+            write!(f, "-{}", strs.join("."))?;
+        }
+        if !self.build.is_empty() {
+            let strs: Vec<_> = self.build.iter().map(ToString::to_string).collect();
+            write!(f, "+{}", strs.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+/// Serializes the version as its canonical string, like `"1.2.3-alpha.1"`.
+#[cfg(feature = "serde")]
+impl Serialize for Version {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserializes the version from a string using [`parse`].
+///
+/// [`parse`]: ./fn.parse.html
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D>(deserializer: D) -> Result<Version, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let input = String::deserialize(deserializer)?;
+        parse(&input).map_err(de::Error::custom)
+    }
+}
+
+/// Converts a version of the [`semver`](https://docs.rs/semver) crate, parsing its prerelease and
+/// build metadata into identifiers.
+///
+/// This fails for identifiers containing a hyphen, like `1.0.0-alpha-1`, which this crate does not
+/// accept.
+#[cfg(feature = "semver-compat")]
+impl<'a> TryFrom<&'a semver::Version> for Version {
+    type Error = ParseError;
+
+    fn try_from(version: &'a semver::Version) -> Result<Version, ParseError> {
+        Version::from_parts(
+            version.major,
+            version.minor,
+            version.patch,
+            version.pre.as_str(),
+            version.build.as_str(),
+        )
+    }
+}
+
+/// Converts the version into one of the [`semver`](https://docs.rs/semver) crate.
+///
+/// This fails for identifiers which are not valid semver, like a hand-built
+/// `Identifier::AlphaNumeric` holding `build/123`.
+#[cfg(feature = "semver-compat")]
+impl<'a> TryFrom<&'a Version> for semver::Version {
+    type Error = semver::Error;
+
+    fn try_from(version: &'a Version) -> Result<semver::Version, semver::Error> {
+        let pre: Vec<_> = version.pre.iter().map(ToString::to_string).collect();
+        let build: Vec<_> = version.build.iter().map(ToString::to_string).collect();
+
+        Ok(semver::Version {
+            major: version.major,
+            minor: version.minor,
+            patch: version.patch,
+            pre: semver::Prerelease::new(&pre.join("."))?,
+            build: semver::BuildMetadata::new(&build.join("."))?,
+        })
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Identifier::Numeric(ref id) => id.fmt(f),
+            Identifier::AlphaNumeric(ref id) => id.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use range;
+    use version;
+    use super::*;
+
+    #[test]
+    fn parse_empty() {
+        let version = "";
+
+        let parsed = version::parse(version);
+
+        assert!(
+            parsed.is_err(),
+            "empty string incorrectly considered a valid parse"
+        );
+    }
+
+    #[test]
+    fn parse_blank() {
+        let version = "  ";
+
+        let parsed = version::parse(version);
+
+        assert!(
+            parsed.is_err(),
+            "blank string incorrectly considered a valid parse"
+        );
+    }
+
+    #[test]
+    #[allow(non_fmt_panics)]
+    fn parse_no_minor_patch() {
+        let version = "1";
+
+        let parsed = version::parse(version);
+
+        assert!(
+            parsed.is_err(),
+            format!("'{}' incorrectly considered a valid parse", version)
+        );
+    }
+
+    #[test]
+    #[allow(non_fmt_panics)]
+    fn parse_no_patch() {
+        let version = "1.2";
+
+        let parsed = version::parse(version);
+
+        assert!(
+            parsed.is_err(),
+            format!("'{}' incorrectly considered a valid parse", version)
+        );
+    }
+
+    #[test]
+    #[allow(non_fmt_panics)]
+    fn parse_empty_pre() {
+        let version = "1.2.3-";
+
+        let parsed = version::parse(version);
+
+        assert!(
+            parsed.is_err(),
+            format!("'{}' incorrectly considered a valid parse", version)
+        );
+    }
+
+    #[test]
+    #[allow(non_fmt_panics)]
+    fn parse_letters() {
+        let version = "a.b.c";
+
+        let parsed = version::parse(version);
+
+        assert!(
+            parsed.is_err(),
+            format!("'{}' incorrectly considered a valid parse", version)
+        );
+    }
+
+    #[test]
+    #[allow(non_fmt_panics)]
+    fn parse_with_letters() {
+        let version = "1.2.3 a.b.c";
+
+        let parsed = version::parse(version);
+
+        assert!(
+            parsed.is_err(),
+            format!("'{}' incorrectly considered a valid parse", version)
+        );
+    }
+
+    #[test]
+    fn parse_basic_version() {
+        let version = "1.2.3";
+
+        let parsed = version::parse(version).unwrap();
+
+        assert_eq!(1, parsed.major);
+        assert_eq!(2, parsed.minor);
+        assert_eq!(3, parsed.patch);
+    }
+
+    #[test]
+    fn parse_trims_input() {
+        let version = "  1.2.3  ";
+
+        let parsed = version::parse(version).unwrap();
+
+        assert_eq!(1, parsed.major);
+        assert_eq!(2, parsed.minor);
+        assert_eq!(3, parsed.patch);
+    }
+
+    #[test]
+    fn parse_no_major_leading_zeroes() {
+        let version = "01.0.0";
+
+        let parsed = version::parse(version);
+
+        assert!(
+            parsed.is_err(),
+            "01 incorrectly considered a valid major version"
+        );
+    }
+
+    #[test]
+    fn parse_no_minor_leading_zeroes() {
+        let version = "0.01.0";
+
+        let parsed = version::parse(version);
+
+        assert!(
+            parsed.is_err(),
+            "01 incorrectly considered a valid minor version"
+        );
+    }
+
+    #[test]
+    fn parse_no_patch_leading_zeroes() {
+        let version = "0.0.01";
+
+        let parsed = version::parse(version);
+
+        assert!(
+            parsed.is_err(),
+            "01 incorrectly considered a valid patch version"
+        );
+    }
+
+    #[test]
+    fn parse_no_major_overflow() {
+        let version = "98765432109876543210.0.0";
+
+        let parsed = version::parse(version);
+
+        assert!(
+            parsed.is_err(),
+            "98765432109876543210 incorrectly considered a valid major version"
+        );
+    }
+
+    #[test]
+    fn parse_no_minor_overflow() {
+        let version = "0.98765432109876543210.0";
+
+        let parsed = version::parse(version);
+
+        assert!(
+            parsed.is_err(),
+            "98765432109876543210 incorrectly considered a valid minor version"
+        );
+    }
+
+    #[test]
+    fn parse_no_patch_overflow() {
+        let version = "0.0.98765432109876543210";
+
+        let parsed = version::parse(version);
+
+        assert!(
+            parsed.is_err(),
+            "98765432109876543210 incorrectly considered a valid patch version"
+        );
+    }
+
+    #[test]
+    fn parse_basic_prerelease() {
+        let version = "1.2.3-pre";
+
+        let parsed = version::parse(version).unwrap();
+
+        let expected_pre = vec![Identifier::AlphaNumeric(String::from("pre"))];
+        assert_eq!(expected_pre, parsed.pre);
+    }
+
+    #[test]
+    fn parse_prerelease_alphanumeric() {
+        let version = "1.2.3-alpha1";
+
+        let parsed = version::parse(version).unwrap();
+
+        let expected_pre = vec![Identifier::AlphaNumeric(String::from("alpha1"))];
+        assert_eq!(expected_pre, parsed.pre);
+    }
+
+    #[test]
+    fn parse_prerelease_zero() {
+        let version = "1.2.3-pre.0";
+
+        let parsed = version::parse(version).unwrap();
+
+        let expected_pre = vec![
+            Identifier::AlphaNumeric(String::from("pre")),
+            Identifier::Numeric(0),
+        ];
+        assert_eq!(expected_pre, parsed.pre);
+    }
+
+    #[test]
+    fn parse_basic_build() {
+        let version = "1.2.3+build";
+
+        let parsed = version::parse(version).unwrap();
+
+        let expected_build = vec![Identifier::AlphaNumeric(String::from("build"))];
+        assert_eq!(expected_build, parsed.build);
+    }
+
+    #[test]
+    fn parse_build_alphanumeric() {
+        let version = "1.2.3+build5";
+
+        let parsed = version::parse(version).unwrap();
+
+        let expected_build = vec![Identifier::AlphaNumeric(String::from("build5"))];
+        assert_eq!(expected_build, parsed.build);
+    }
+
+    #[test]
+    fn parse_pre_and_build() {
+        let version = "1.2.3-alpha1+build5";
+
+        let parsed = version::parse(version).unwrap();
+
+        let expected_pre = vec![Identifier::AlphaNumeric(String::from("alpha1"))];
+        assert_eq!(expected_pre, parsed.pre);
+
+        let expected_build = vec![Identifier::AlphaNumeric(String::from("build5"))];
+        assert_eq!(expected_build, parsed.build);
+    }
+
+    #[test]
+    fn parse_complex_metadata_01() {
+        let version = "1.2.3-1.alpha1.9+build5.7.3aedf  ";
+
+        let parsed = version::parse(version).unwrap();
+
+        let expected_pre = vec![
+            Identifier::Numeric(1),
+            Identifier::AlphaNumeric(String::from("alpha1")),
+            Identifier::Numeric(9),
+        ];
+        assert_eq!(expected_pre, parsed.pre);
+
+        let expected_build = vec![
+            Identifier::AlphaNumeric(String::from("build5")),
+            Identifier::Numeric(7),
+            Identifier::AlphaNumeric(String::from("3aedf")),
+        ];
+        assert_eq!(expected_build, parsed.build);
+    }
+
+    #[test]
+    fn parse_complex_metadata_02() {
+        let version = "0.4.0-beta.1+0851523";
+
+        let parsed = version::parse(version).unwrap();
+
+        let expected_pre = vec![
+            Identifier::AlphaNumeric(String::from("beta")),
+            Identifier::Numeric(1),
+        ];
+        assert_eq!(expected_pre, parsed.pre);
+
+        let expected_build = vec![Identifier::AlphaNumeric(String::from("0851523"))];
+        assert_eq!(expected_build, parsed.build);
+    }
+
+    #[test]
+    fn parse_metadata_overflow() {
+        let version = "0.4.0-beta.1+98765432109876543210";
+
+        let parsed = version::parse(version).unwrap();
+
+        let expected_pre = vec![
+            Identifier::AlphaNumeric(String::from("beta")),
+            Identifier::Numeric(1),
+        ];
+        assert_eq!(expected_pre, parsed.pre);
+
+        let expected_build = vec![
+            Identifier::AlphaNumeric(String::from("98765432109876543210")),
+        ];
+        assert_eq!(expected_build, parsed.build);
+    }
+
+    #[test]
+    fn parse_regression_01() {
+        let version = "0.0.0-WIP";
+
+        let parsed = version::parse(version).unwrap();
+
+        assert_eq!(0, parsed.major);
+        assert_eq!(0, parsed.minor);
+        assert_eq!(0, parsed.patch);
+
+        let expected_pre = vec![Identifier::AlphaNumeric(String::from("WIP"))];
+        assert_eq!(expected_pre, parsed.pre);
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let versions = [
+            "0.0.0",
+            "1.2.3",
+            "1.2.3-alpha",
+            "1.2.3-alpha.1",
+            "1.2.3-0.3.7",
+            "1.2.3+build",
+            "1.2.3+build.5",
+            "1.2.3-alpha.1+build.5",
+            "1.2.3-x.7.z.92+20130313144700",
+            "0.4.0-beta.1+0851523",
+        ];
+
+        for version in versions.iter() {
+            let parsed = version::parse(version).unwrap();
+            let displayed = parsed.to_string();
+
+            assert_eq!(*version, displayed);
+            assert_eq!(parsed, version::parse(&displayed).unwrap());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        use serde_json;
+
+        let versions = ["1.2.3", "0.0.0-WIP", "1.2.3-alpha.1+build.5"];
+
+        for version in versions.iter() {
+            let parsed = version::parse(version).unwrap();
+            let json = serde_json::to_string(&parsed).unwrap();
+
+            assert_eq!(format!("\"{}\"", version), json);
+            assert_eq!(parsed, serde_json::from_str::<Version>(&json).unwrap());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_invalid() {
+        use serde_json;
+
+        assert!(serde_json::from_str::<Version>("\"1.2\"").is_err());
+        assert!(serde_json::from_str::<Version>("42").is_err());
+    }
+
+    #[cfg(feature = "semver-compat")]
+    #[test]
+    fn semver_round_trip() {
+        use semver;
+
+        let versions = ["1.2.3", "0.0.0-WIP", "1.2.3-alpha.1+build.5", "1.0.0+001"];
+
+        for version in versions.iter() {
+            let parsed = version::parse(version).unwrap();
+            let converted = semver::Version::try_from(&parsed).unwrap();
+
+            assert_eq!(semver::Version::parse(version).unwrap(), converted);
+            assert_eq!(parsed, Version::try_from(&converted).unwrap());
+            assert_eq!(parsed.build, Version::try_from(&converted).unwrap().build);
+        }
+    }
+
+    #[cfg(feature = "semver-compat")]
+    #[test]
+    fn semver_unsupported() {
+        use semver;
+
+        let mut tolerant = Version::new(1, 2, 3);
+        tolerant.build = vec![Identifier::AlphaNumeric(String::from("build/123"))];
+        let hyphenated = semver::Version::parse("1.0.0-alpha-1").unwrap();
+
+        assert!(semver::Version::try_from(&tolerant).is_err());
+        assert!(Version::try_from(&hyphenated).is_err());
+    }
+
+    #[test]
+    fn diff() {
+        let cases = [
+            ("1.0.0", "2.0.0", Some(VersionDiff::Major)),
+            ("1.0.0", "1.1.0", Some(VersionDiff::Minor)),
+            ("1.0.0", "1.0.1", Some(VersionDiff::Patch)),
+            ("1.0.0-a", "1.0.0-b", Some(VersionDiff::Pre)),
+            ("1.0.0", "1.0.0-b", Some(VersionDiff::Pre)),
+            ("1.0.0-a", "2.0.0-a", Some(VersionDiff::Major)),
+            ("1.0.0+a", "1.0.0+b", None),
+            ("1.0.0", "1.0.0", None),
+        ];
+
+        for &(a, b, expected) in cases.iter() {
+            let a = version::parse(a).unwrap();
+            let b = version::parse(b).unwrap();
+
+            assert_eq!(expected, a.diff(&b));
+            assert_eq!(expected, b.diff(&a));
+            assert_eq!(expected.is_none(), a.precedence_eq(&b));
+        }
+        assert_eq!("pre", VersionDiff::Pre.to_string());
+    }
+
+    #[test]
+    fn to_string_no_build() {
+        let cases = [
+            ("1.2.3-rc.1+build.9", "1.2.3-rc.1"),
+            ("1.2.3+build", "1.2.3"),
+            ("0.0.0-alpha.0.x", "0.0.0-alpha.0.x"),
+            ("1.2.3", "1.2.3"),
+        ];
+
+        for &(input, expected) in cases.iter() {
+            let parsed = version::parse(input).unwrap();
+            let options = FormatOptions {
+                omit_build: true,
+                ..FormatOptions::default()
+            };
+
+            assert_eq!(expected, parsed.to_string_no_build());
+            assert_eq!(parsed.format(options), parsed.to_string_no_build());
+        }
+    }
+
+    #[test]
+    fn increment_major() {
+        let mut version = version::parse("1.2.3-alpha+build").unwrap();
+        version.increment_major();
+
+        assert_eq!(2, version.major);
+        assert_eq!(0, version.minor);
+        assert_eq!(0, version.patch);
+        assert!(version.pre.is_empty());
+        assert!(version.build.is_empty());
+    }
+
+    #[test]
+    fn increment_minor() {
+        let mut version = version::parse("1.2.3-alpha+build").unwrap();
+        version.increment_minor();
+
+        assert_eq!(1, version.major);
+        assert_eq!(3, version.minor);
+        assert_eq!(0, version.patch);
+        assert!(version.pre.is_empty());
+        assert!(version.build.is_empty());
+    }
+
+    #[test]
+    fn increment_patch() {
+        let mut version = version::parse("1.2.3-alpha+build").unwrap();
+        version.increment_patch();
+
+        assert_eq!(1, version.major);
+        assert_eq!(2, version.minor);
+        assert_eq!(4, version.patch);
+        assert!(version.pre.is_empty());
+        assert!(version.build.is_empty());
+    }
+
+    #[test]
+    fn from_str() {
+        let parsed: Version = "1.2.3-alpha1".parse().unwrap();
+
+        assert_eq!(version::parse("1.2.3-alpha1").unwrap(), parsed);
+        assert!("1.2".parse::<Version>().is_err());
+        assert!("a.b.c".parse::<Version>().is_err());
+    }
+
+    #[test]
+    fn parse_lenient_prefix() {
+        let expected = version::parse("1.2.3").unwrap();
+
+        assert_eq!(expected, version::parse_lenient("v1.2.3").unwrap());
+        assert_eq!(expected, version::parse_lenient("V1.2.3").unwrap());
+        assert_eq!(expected, version::parse_lenient("  1.2.3  ").unwrap());
+        assert_eq!(expected, version::parse_lenient("\tv1.2.3\n").unwrap());
+        assert_eq!(
+            version::parse("1.2.3-rc.1+build").unwrap(),
+            version::parse_lenient("v1.2.3-rc.1+build").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_lenient_invalid() {
+        assert!(version::parse_lenient("vv1.2.3").is_err());
+        assert!(version::parse_lenient("v").is_err());
+        assert!(version::parse_lenient("x1.2.3").is_err());
+        assert!(version::parse_lenient("v1.2").is_err());
+        assert_eq!(Some(6), version::parse_lenient("  v1.2.3 a").unwrap_err().position());
+        assert_eq!(Some(4), version::parse_lenient("\t1.2.x").unwrap_err().position());
+    }
+
+    #[test]
+    fn parse_strict_rejects_prefix() {
+        assert!(version::parse("v1.2.3").is_err());
+        assert!(version::parse("V1.2.3").is_err());
+    }
+
+    #[test]
+    fn parse_partial_major() {
+        let parsed = version::parse_partial("1").unwrap();
+
+        assert_eq!(version::parse("1.0.0").unwrap(), parsed);
+    }
+
+    #[test]
+    fn parse_partial_major_minor() {
+        let parsed = version::parse_partial("1.2").unwrap();
+
+        assert_eq!(version::parse("1.2.0").unwrap(), parsed);
+    }
+
+    #[test]
+    fn parse_partial_complete() {
+        let version = "1.2.3-alpha1+build5";
+
+        assert_eq!(
+            version::parse(version).unwrap(),
+            version::parse_partial(version).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_partial_invalid() {
+        assert!(version::parse_partial("").is_err());
+        assert!(version::parse_partial("1.").is_err());
+        assert!(version::parse_partial("1.2.").is_err());
+        assert!(version::parse_partial("1.2.3.4").is_err());
+        assert!(version::parse_partial("1.*").is_err());
+    }
+
+    #[test]
+    fn is_prerelease() {
+        assert!(version::parse("1.2.3-alpha1").unwrap().is_prerelease());
+        assert!(version::parse("0.0.0-0").unwrap().is_prerelease());
+        assert!(!version::parse("1.2.3").unwrap().is_prerelease());
+        assert!(!version::parse("1.2.3+build").unwrap().is_prerelease());
+    }
+
+    #[test]
+    fn precedence() {
+        let chain = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.1.0",
+            "2.0.0",
+        ];
+
+        for pair in chain.windows(2) {
+            let (a, b) = (parse(pair[0]).unwrap(), parse(pair[1]).unwrap());
+            assert!(a < b, "{} < {}", a, b);
+            assert!(b > a, "{} > {}", b, a);
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use version;
-    use super::*;
+    #[test]
+    fn precedence_ignores_build() {
+        let a = parse("1.0.0+build.1").unwrap();
+        let b = parse("1.0.0+build.2").unwrap();
+
+        assert_eq!(Ordering::Equal, compare(&a, &b));
+        assert_eq!(Ordering::Less, a.cmp(&b));
+        assert_ne!(a, b);
+        assert!(parse("1.0.0").unwrap() < a);
+        assert!(b < parse("1.0.1-alpha").unwrap());
+        assert!(parse("1.0.0-alpha+zzz").unwrap() < parse("1.0.0-beta+aaa").unwrap());
+    }
 
     #[test]
-    fn parse_empty() {
-        let version = "";
+    fn identifier_accessors() {
+        let numeric = Identifier::Numeric(42);
+        let alpha = Identifier::AlphaNumeric(String::from("beta2"));
 
-        let parsed = version::parse(version);
+        assert!(numeric.is_numeric());
+        assert_eq!(Some(42), numeric.as_u64());
+        assert_eq!(None, numeric.as_str());
 
-        assert!(
-            parsed.is_err(),
-            "empty string incorrectly considered a valid parse"
-        );
+        assert!(!alpha.is_numeric());
+        assert_eq!(None, alpha.as_u64());
+        assert_eq!(Some("beta2"), alpha.as_str());
     }
 
     #[test]
-    fn parse_blank() {
-        let version = "  ";
-
-        let parsed = version::parse(version);
+    fn new() {
+        let v = Version::new(1, 2, 3);
 
-        assert!(
-            parsed.is_err(),
-            "blank string incorrectly considered a valid parse"
-        );
+        assert_eq!(version::parse("1.2.3").unwrap(), v);
+        assert!(v.pre.is_empty());
+        assert!(v.build.is_empty());
     }
 
     #[test]
-    fn parse_no_minor_patch() {
-        let version = "1";
+    fn parse_many() {
+        let blob = "1.0.0\n  0.2.1-alpha.1+build\n\nnot-a-version\t1.2\r\n3.4.5\n";
+        let results: Vec<_> = version::parse_many(blob).collect();
 
-        let parsed = version::parse(version);
+        let pattern: Vec<_> = results.iter().map(Result::is_ok).collect();
+        assert_eq!(vec![true, true, false, false, true], pattern);
 
-        assert!(
-            parsed.is_err(),
-            format!("'{}' incorrectly considered a valid parse", version)
-        );
+        assert_eq!(Version::new(1, 0, 0), results[0].clone().unwrap());
+        assert_eq!(Version::new(3, 4, 5), results[4].clone().unwrap());
     }
 
     #[test]
-    fn parse_no_patch() {
-        let version = "1.2";
+    fn parse_many_empty() {
+        assert_eq!(0, version::parse_many("").count());
+        assert_eq!(0, version::parse_many(" \n\t ").count());
+    }
 
-        let parsed = version::parse(version);
+    #[test]
+    fn compare_sorts() {
+        let canonical = [
+            "0.9.0",
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.10.0",
+            "2.0.0",
+        ];
+        let shuffled = [
+            "1.0.0-beta.11",
+            "2.0.0",
+            "1.0.0-alpha.beta",
+            "1.0.0",
+            "1.0.0-alpha",
+            "1.10.0",
+            "0.9.0",
+            "1.0.0-rc.1",
+            "1.0.0-beta",
+            "1.0.1",
+            "1.0.0-alpha.1",
+            "1.0.0-beta.2",
+        ];
 
-        assert!(
-            parsed.is_err(),
-            format!("'{}' incorrectly considered a valid parse", version)
-        );
+        let mut versions: Vec<_> = shuffled.iter().map(|v| parse(v).unwrap()).collect();
+        versions.sort_by(version::compare);
+
+        let sorted: Vec<_> = versions.iter().map(ToString::to_string).collect();
+        assert_eq!(canonical.to_vec(), sorted);
     }
 
     #[test]
-    fn parse_empty_pre() {
-        let version = "1.2.3-";
-
-        let parsed = version::parse(version);
+    fn compare_ignores_build() {
+        let a = parse("1.0.0+build.1").unwrap();
+        let b = parse("1.0.0+build.2").unwrap();
 
-        assert!(
-            parsed.is_err(),
-            format!("'{}' incorrectly considered a valid parse", version)
-        );
+        assert_eq!(Ordering::Equal, version::compare(&a, &b));
     }
 
     #[test]
-    fn parse_letters() {
-        let version = "a.b.c";
+    fn satisfies() {
+        let r = range::parse(">= 1.2.0, < 2.0.0").unwrap();
 
-        let parsed = version::parse(version);
+        assert!(parse("1.2.0").unwrap().satisfies(&r));
+        assert!(parse("1.9.9").unwrap().satisfies(&r));
+        assert!(!parse("1.1.9").unwrap().satisfies(&r));
+        assert!(!parse("2.0.0").unwrap().satisfies(&r));
+        assert!(!parse("1.5.0-alpha").unwrap().satisfies(&r));
 
-        assert!(
-            parsed.is_err(),
-            format!("'{}' incorrectly considered a valid parse", version)
-        );
+        let pre = range::parse(">= 1.2.0-alpha").unwrap();
+        assert!(parse("1.2.0-beta").unwrap().satisfies(&pre));
+        assert!(!parse("1.3.0-beta").unwrap().satisfies(&pre));
+
+        assert!(parse("0.0.1").unwrap().satisfies(&range::parse("*").unwrap()));
     }
 
     #[test]
-    fn parse_with_letters() {
-        let version = "1.2.3 a.b.c";
-
-        let parsed = version::parse(version);
+    fn parse_saturating() {
+        let max = u64::MAX;
+
+        let (v, overflowed) = version::parse_saturating("18446744073709551617.0.0").unwrap();
+        assert_eq!(Version::new(max, 0, 0), v);
+        assert!(overflowed);
+
+        let (v, overflowed) =
+            version::parse_saturating("1.99999999999999999999999.3-alpha+build").unwrap();
+        assert_eq!((1, max, 3), (v.major, v.minor, v.patch));
+        assert_eq!(parse("0.0.0-alpha+build").unwrap().pre, v.pre);
+        assert!(overflowed);
+
+        let (v, overflowed) = version::parse_saturating("0.0.18446744073709551616").unwrap();
+        assert_eq!(Version::new(0, 0, max), v);
+        assert!(overflowed);
+    }
 
-        assert!(
-            parsed.is_err(),
-            format!("'{}' incorrectly considered a valid parse", version)
+    #[test]
+    fn parse_saturating_in_range() {
+        let (v, overflowed) = version::parse_saturating("1.2.3-rc.1").unwrap();
+
+        assert_eq!(parse("1.2.3-rc.1").unwrap(), v);
+        assert!(!overflowed);
+        assert_eq!(
+            (Version::new(u64::MAX, 0, 0), false),
+            version::parse_saturating("18446744073709551615.0.0").unwrap()
         );
     }
 
     #[test]
-    fn parse_basic_version() {
-        let version = "1.2.3";
+    fn parse_saturating_errors() {
+        assert!(version::parse_saturating("1.2").is_err());
+        assert!(version::parse_saturating("01.2.3").is_err());
+        assert!(version::parse_saturating("1.2.3a").is_err());
+        assert!(version::parse_saturating("1.2.3 4").is_err());
+    }
 
-        let parsed = version::parse(version).unwrap();
+    #[test]
+    fn clear_pre() {
+        let mut v = parse("1.2.3-alpha.1+build.5").unwrap();
+        v.clear_pre();
 
-        assert_eq!(1, parsed.major);
-        assert_eq!(2, parsed.minor);
-        assert_eq!(3, parsed.patch);
+        assert!(v.pre.is_empty());
+        assert_eq!((1, 2, 3), (v.major, v.minor, v.patch));
+        assert_eq!(parse("0.0.0+build.5").unwrap().build, v.build);
     }
 
     #[test]
-    fn parse_trims_input() {
-        let version = "  1.2.3  ";
-
-        let parsed = version::parse(version).unwrap();
+    fn clear_build() {
+        let mut v = parse("1.2.3-alpha.1+build.5").unwrap();
+        v.clear_build();
 
-        assert_eq!(1, parsed.major);
-        assert_eq!(2, parsed.minor);
-        assert_eq!(3, parsed.patch);
+        assert!(v.build.is_empty());
+        assert_eq!((1, 2, 3), (v.major, v.minor, v.patch));
+        assert_eq!(parse("0.0.0-alpha.1").unwrap().pre, v.pre);
     }
 
     #[test]
-    fn parse_no_major_leading_zeroes() {
-        let version = "01.0.0";
+    fn without_pre_and_build() {
+        let v = parse("1.2.3-alpha.1+build.5").unwrap();
 
-        let parsed = version::parse(version);
+        assert_eq!("1.2.3+build.5", v.without_pre().to_string());
+        assert_eq!("1.2.3-alpha.1", v.without_build().to_string());
+        assert_eq!("1.2.3", v.without_pre().without_build().to_string());
+        assert_eq!("1.2.3-alpha.1+build.5", v.to_string());
+    }
 
-        assert!(
-            parsed.is_err(),
-            "01 incorrectly considered a valid major version"
+    #[test]
+    fn compare_case() {
+        let upper = parse("1.0.0-Alpha.1").unwrap();
+        let lower = parse("1.0.0-alpha.1").unwrap();
+        let beta = parse("1.0.0-BETA").unwrap();
+
+        // identifiers are compared in ASCII order by default, so uppercase sorts first.
+        assert_eq!(Ordering::Less, version::compare(&upper, &lower));
+        assert_eq!(Ordering::Less, version::compare(&beta, &lower));
+        assert_ne!(upper, lower);
+
+        assert_eq!(Ordering::Equal, version::compare_ignore_pre_case(&upper, &lower));
+        assert_eq!(Ordering::Greater, version::compare_ignore_pre_case(&beta, &lower));
+        assert_eq!(
+            Ordering::Less,
+            version::compare_ignore_pre_case(&upper, &parse("1.0.0-ALPHA.2").unwrap())
+        );
+        assert_eq!(
+            Ordering::Less,
+            version::compare_ignore_pre_case(&upper, &parse("1.0.0").unwrap())
+        );
+        assert_eq!(
+            Ordering::Greater,
+            version::compare_ignore_pre_case(&upper, &parse("1.0.0-alpha").unwrap())
         );
     }
 
     #[test]
-    fn parse_no_minor_leading_zeroes() {
-        let version = "0.01.0";
+    fn try_from() {
+        use core::convert::TryInto;
 
-        let parsed = version::parse(version);
+        assert_eq!(Ok(Version::new(1, 2, 3)), Version::try_from("1.2.3"));
 
-        assert!(
-            parsed.is_err(),
-            "01 incorrectly considered a valid minor version"
-        );
+        let version: Result<Version, _> = "1.2.3-alpha".try_into();
+        assert_eq!(parse("1.2.3-alpha").unwrap(), version.unwrap());
+
+        let error = Version::try_from("1.2").unwrap_err();
+        assert_eq!("expected more input", error.to_string());
     }
 
     #[test]
-    fn parse_no_patch_leading_zeroes() {
-        let version = "0.0.01";
+    fn partial_with_pre() {
+        assert!(parse("0-Foo").is_err());
 
-        let parsed = version::parse(version);
+        let v = version::parse_partial("0-Foo").unwrap();
+        assert_eq!(parse("0.0.0-Foo").unwrap(), v);
 
-        assert!(
-            parsed.is_err(),
-            "01 incorrectly considered a valid patch version"
+        let p = &range::parse("0-Foo").unwrap().predicates[0];
+        assert_eq!((v.major, None, None), (p.major, p.minor, p.patch));
+        assert_eq!(v.pre, p.pre);
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        assert_eq!(parse("1.2.3"), parse_bytes(b"1.2.3"));
+        assert_eq!(parse("1.2.3-alpha.1+build"), parse_bytes(b"1.2.3-alpha.1+build"));
+        assert_eq!(parse("1.2"), parse_bytes(b"1.2"));
+
+        assert_eq!(
+            Err(parser::Error::Lexer(::lexer::Error::UnexpectedChar('\u{fffd}', 4))),
+            parse_bytes(b"1.2.\xff")
         );
     }
 
     #[test]
-    fn parse_no_major_overflow() {
-        let version = "98765432109876543210.0.0";
+    fn test_major_minor_patch() {
+        assert_eq!((1, 2, 3), parse("1.2.3").unwrap().major_minor_patch());
+        assert_eq!((0, 10, 7), parse("0.10.7-rc.1+build").unwrap().major_minor_patch());
+        assert_eq!((4, 0, 0), Version::new(4, 0, 0).major_minor_patch());
+    }
 
-        let parsed = version::parse(version);
+    #[test]
+    fn test_parse_interned() {
+        let mut interner = Interner::new();
 
-        assert!(
-            parsed.is_err(),
-            "98765432109876543210 incorrectly considered a valid major version"
+        let inputs = ["1.2.3", "1.2.3-alpha.1", "1.2.3-rc.1+build.5", "0.1.0-beta+exp.sha.5114f85"];
+
+        for input in &inputs {
+            let interned = parse_interned(input, &mut interner).unwrap();
+            let parsed = parse(input).unwrap();
+
+            assert_eq!(interned, parsed);
+            assert_eq!(parsed, Version::from(&interned));
+        }
+
+        let interned = parse_interned("1.2.3+a", &mut interner).unwrap();
+        assert!(interned != parse("1.2.3+b").unwrap());
+
+        assert_eq!(
+            parse("1.2.3-01").unwrap_err(),
+            parse_interned("1.2.3-01", &mut interner).unwrap_err()
+        );
+        assert_eq!(
+            parse("1.2.3-alpha..1").unwrap_err(),
+            parse_interned("1.2.3-alpha..1", &mut interner).unwrap_err()
         );
+        assert!(parse_interned("1.2.3 foo", &mut interner).is_err());
     }
 
     #[test]
-    fn parse_no_minor_overflow() {
-        let version = "0.98765432109876543210.0";
+    fn test_parse_interned_many() {
+        let mut interner = Interner::new();
+        let tags = ["alpha", "beta", "rc"];
+        let mut versions = Vec::new();
+
+        for i in 0..10_000u64 {
+            let input = format!("1.{}.0-{}.{}", i, tags[(i % 3) as usize], i);
+            versions.push(parse_interned(&input, &mut interner).unwrap());
+        }
 
-        let parsed = version::parse(version);
+        assert_eq!(3, interner.len());
 
-        assert!(
-            parsed.is_err(),
-            "98765432109876543210 incorrectly considered a valid minor version"
-        );
+        match (&versions[0].pre[0], &versions[3].pre[0]) {
+            (InternedIdentifier::AlphaNumeric(a), InternedIdentifier::AlphaNumeric(b)) => {
+                assert!(Arc::ptr_eq(a, b));
+            }
+            _ => panic!("expected alphanumeric identifiers"),
+        }
     }
 
     #[test]
-    fn parse_no_patch_overflow() {
-        let version = "0.0.98765432109876543210";
+    fn test_parse_spanned() {
+        let (version, spans) = parse_spanned("1.2.3-alpha.1").unwrap();
+        assert_eq!(parse("1.2.3-alpha.1").unwrap(), version);
+        assert_eq!(
+            VersionSpans {
+                major: 0..1,
+                minor: 2..3,
+                patch: 4..5,
+                pre: Some(6..13),
+                build: None,
+            },
+            spans
+        );
 
-        let parsed = version::parse(version);
+        let input = " 10.200.3000+build.7 ";
+        let (_, spans) = parse_spanned(input).unwrap();
+        assert_eq!("10", &input[spans.major]);
+        assert_eq!("200", &input[spans.minor]);
+        assert_eq!("3000", &input[spans.patch]);
+        assert_eq!(None, spans.pre);
+        assert_eq!(Some("build.7"), spans.build.map(|span| &input[span]));
 
-        assert!(
-            parsed.is_err(),
-            "98765432109876543210 incorrectly considered a valid patch version"
-        );
+        assert_eq!(parse("1.2.3-01").unwrap_err(), parse_spanned("1.2.3-01").unwrap_err());
+        assert!(parse_spanned("1.2.3 x").is_err());
     }
 
     #[test]
-    fn parse_basic_prerelease() {
-        let version = "1.2.3-pre";
+    fn test_checked_increment() {
+        let version = parse("1.2.3-alpha.1+build").unwrap();
+
+        assert_eq!(Some(Version::new(2, 0, 0)), version.checked_increment_major());
+        assert_eq!(Some(Version::new(1, 3, 0)), version.checked_increment_minor());
+        assert_eq!(Some(Version::new(1, 2, 4)), version.checked_increment_patch());
+        assert!(version.checked_increment_patch().unwrap().build.is_empty());
+
+        let max = Version::new(u64::MAX, u64::MAX, u64::MAX);
+        assert_eq!(None, max.checked_increment_major());
+        assert_eq!(None, max.checked_increment_minor());
+        assert_eq!(None, max.checked_increment_patch());
+
+        let major_max = Version::new(u64::MAX, 1, 2);
+        assert_eq!(None, major_max.checked_increment_major());
+        assert_eq!(Some(Version::new(u64::MAX, 2, 0)), major_max.checked_increment_minor());
+    }
 
-        let parsed = version::parse(version).unwrap();
+    #[test]
+    fn test_in_series() {
+        let version = parse("1.2.3").unwrap();
 
-        let expected_pre = vec![Identifier::AlphaNumeric(String::from("pre"))];
-        assert_eq!(expected_pre, parsed.pre);
+        assert!(version.in_series(1, None));
+        assert!(version.in_series(1, Some(2)));
+        assert!(!version.in_series(1, Some(3)));
+        assert!(!version.in_series(2, None));
+        assert!(!version.in_series(2, Some(2)));
+        assert!(!version.in_series(0, Some(1)));
+
+        assert!(parse("0.1.0-beta+build").unwrap().in_series(0, Some(1)));
     }
 
     #[test]
-    fn parse_prerelease_alphanumeric() {
-        let version = "1.2.3-alpha1";
+    fn test_from_parts() {
+        let version = Version::from_parts(1, 2, 3, "alpha.1.x7", "build.007").unwrap();
+        assert_eq!(
+            vec![
+                Identifier::AlphaNumeric("alpha".to_string()),
+                Identifier::Numeric(1),
+                Identifier::AlphaNumeric("x7".to_string()),
+            ],
+            version.pre
+        );
+        assert_eq!(
+            vec![
+                Identifier::AlphaNumeric("build".to_string()),
+                Identifier::AlphaNumeric("007".to_string()),
+            ],
+            version.build
+        );
 
-        let parsed = version::parse(version).unwrap();
+        let version = Version::from_parts(1, 2, 3, "", "").unwrap();
+        assert!(version.pre.is_empty());
+        assert!(version.build.is_empty());
+        assert_eq!(Version::new(1, 2, 3), version);
 
-        let expected_pre = vec![Identifier::AlphaNumeric(String::from("alpha1"))];
-        assert_eq!(expected_pre, parsed.pre);
+        assert_eq!(
+            parse("0.1.0+7").unwrap().build,
+            Version::from_parts(0, 1, 0, "", "7").unwrap().build
+        );
+
+        assert_eq!(Some(6), Version::from_parts(1, 2, 3, "alpha.", "").unwrap_err().position());
+        assert!(Version::from_parts(1, 2, 3, "01", "").is_err());
+        assert!(Version::from_parts(1, 2, 3, "alpha+build", "").is_err());
+        assert!(Version::from_parts(1, 2, 3, "", "build 5").is_err());
     }
 
     #[test]
-    fn parse_prerelease_zero() {
-        let version = "1.2.3-pre.0";
-
-        let parsed = version::parse(version).unwrap();
+    fn test_is_zero() {
+        assert!(parse("0.0.0").unwrap().is_zero());
+        assert!(Version::new(0, 0, 0).is_zero());
+        assert!(!parse("0.0.0-alpha").unwrap().is_zero());
+        assert!(!parse("0.0.0+build").unwrap().is_zero());
+        assert!(!parse("0.0.1").unwrap().is_zero());
+        assert!(!parse("0.1.0").unwrap().is_zero());
+        assert!(!parse("1.0.0").unwrap().is_zero());
+    }
 
-        let expected_pre = vec![
-            Identifier::AlphaNumeric(String::from("pre")),
-            Identifier::Numeric(0),
+    #[test]
+    fn test_identifier_ord() {
+        let alpha = |s: &str| Identifier::AlphaNumeric(s.to_string());
+
+        assert!(Identifier::Numeric(2) < Identifier::Numeric(10));
+        assert!(Identifier::Numeric(999) < alpha("a"));
+        assert!(Identifier::Numeric(u64::MAX) < alpha("0a"));
+        assert!(alpha("alpha") < alpha("beta"));
+        assert!(alpha("Beta") < alpha("alpha"));
+        assert!(alpha("rc") < alpha("rc1"));
+        assert!(alpha("10") < alpha("9"));
+        assert_eq!(Ordering::Equal, alpha("rc").cmp(&alpha("rc")));
+
+        let mut identifiers = vec![
+            alpha("b"),
+            Identifier::Numeric(10),
+            alpha("a"),
+            Identifier::Numeric(2),
         ];
-        assert_eq!(expected_pre, parsed.pre);
+        identifiers.sort();
+        assert_eq!(
+            vec![Identifier::Numeric(2), Identifier::Numeric(10), alpha("a"), alpha("b")],
+            identifiers
+        );
     }
 
     #[test]
-    fn parse_basic_build() {
-        let version = "1.2.3+build";
+    fn test_format() {
+        let version = parse("1.2.3-rc.1+build.9").unwrap();
+        let cases = [
+            (false, false, false, "1.2.3-rc.1+build.9"),
+            (true, false, false, "v1.2.3-rc.1+build.9"),
+            (false, true, false, "1.2.3+build.9"),
+            (false, false, true, "1.2.3-rc.1"),
+            (true, true, false, "v1.2.3+build.9"),
+            (true, false, true, "v1.2.3-rc.1"),
+            (false, true, true, "1.2.3"),
+            (true, true, true, "v1.2.3"),
+        ];
 
-        let parsed = version::parse(version).unwrap();
+        for &(leading_v, omit_pre, omit_build, expected) in cases.iter() {
+            let options = FormatOptions {
+                leading_v,
+                omit_pre,
+                omit_build,
+            };
 
-        let expected_build = vec![Identifier::AlphaNumeric(String::from("build"))];
-        assert_eq!(expected_build, parsed.build);
+            assert_eq!(expected, version.format(options), "{:?}", options);
+        }
+
+        assert_eq!(version.to_string(), version.format(FormatOptions::default()));
+        assert_eq!(
+            "v1.0.0",
+            Version::new(1, 0, 0).format(FormatOptions {
+                leading_v: true,
+                ..FormatOptions::default()
+            })
+        );
     }
 
     #[test]
-    fn parse_build_alphanumeric() {
-        let version = "1.2.3+build5";
-
-        let parsed = version::parse(version).unwrap();
-
-        let expected_build = vec![Identifier::AlphaNumeric(String::from("build5"))];
-        assert_eq!(expected_build, parsed.build);
+    fn test_parse_unicode_digits() {
+        assert_eq!(
+            Err(parser::Error::Lexer(::lexer::Error::UnexpectedChar('\u{0661}', 0))),
+            parse("\u{0661}.0.0")
+        );
+        assert_eq!(
+            Err(parser::Error::Lexer(::lexer::Error::UnexpectedChar('\u{0663}', 4))),
+            parse("1.2.\u{0663}")
+        );
+        assert!(parse("1.2.3-\u{0661}").is_err());
+        assert!(parse("1.2.3+\u{00bd}").is_err());
     }
 
     #[test]
-    fn parse_pre_and_build() {
-        let version = "1.2.3-alpha1+build5";
+    fn test_bump() {
+        let bumped = |input: &str, level| {
+            let mut version = parse(input).unwrap();
+            version.bump(level);
+            version.to_string()
+        };
+
+        assert_eq!("2.0.0", bumped("1.2.3-alpha.1+build.5", Level::Major));
+        assert_eq!("1.3.0", bumped("1.2.3-alpha.1+build.5", Level::Minor));
+        assert_eq!("1.2.4", bumped("1.2.3-alpha.1+build.5", Level::Patch));
+        assert_eq!("1.0.0", bumped("0.9.9", Level::Major));
+        assert_eq!("0.10.0", bumped("0.9.9", Level::Minor));
+        assert_eq!("0.9.10", bumped("0.9.9", Level::Patch));
+    }
 
-        let parsed = version::parse(version).unwrap();
+    #[test]
+    fn test_level_from_str() {
+        assert_eq!(Ok(Level::Major), "major".parse());
+        assert_eq!(Ok(Level::Minor), "minor".parse());
+        assert_eq!(Ok(Level::Patch), "patch".parse());
 
-        let expected_pre = vec![Identifier::AlphaNumeric(String::from("alpha1"))];
-        assert_eq!(expected_pre, parsed.pre);
+        assert!("Major".parse::<Level>().is_err());
+        assert!("pre".parse::<Level>().is_err());
+        assert!("".parse::<Level>().is_err());
 
-        let expected_build = vec![Identifier::AlphaNumeric(String::from("build5"))];
-        assert_eq!(expected_build, parsed.build);
+        for level in &[Level::Major, Level::Minor, Level::Patch] {
+            assert_eq!(Ok(*level), level.to_string().parse());
+        }
     }
 
     #[test]
-    fn parse_complex_metadata_01() {
-        let version = "1.2.3-1.alpha1.9+build5.7.3aedf  ";
-
-        let parsed = version::parse(version).unwrap();
-
-        let expected_pre = vec![
-            Identifier::Numeric(1),
-            Identifier::AlphaNumeric(String::from("alpha1")),
-            Identifier::Numeric(9),
+    fn test_is_valid() {
+        let inputs = [
+            "1.2.3",
+            "  1.2.3  ",
+            "0.0.0",
+            "1.2.3-alpha.1",
+            "1.2.3-0.alpha",
+            "1.2.3+build.01",
+            "1.2.3-alpha.1+build.5",
+            "18446744073709551615.0.0",
+            "",
+            "1",
+            "1.2",
+            "1.2.3.4",
+            "01.2.3",
+            "1.2.3-01",
+            "1.2.3-",
+            "1.2.3+",
+            "1.2.3-alpha..1",
+            "1.2.3 4",
+            "1.2.3, 4",
+            "^1.2.3",
+            "1.2.*",
+            "18446744073709551616.0.0",
+            "\u{0661}.0.0",
         ];
-        assert_eq!(expected_pre, parsed.pre);
 
-        let expected_build = vec![
-            Identifier::AlphaNumeric(String::from("build5")),
-            Identifier::Numeric(7),
-            Identifier::AlphaNumeric(String::from("3aedf")),
-        ];
-        assert_eq!(expected_build, parsed.build);
+        for input in &inputs {
+            assert_eq!(parse(input).is_ok(), is_valid(input), "{}", input);
+        }
     }
 
     #[test]
-    fn parse_complex_metadata_02() {
-        let version = "0.4.0-beta.1+0851523";
+    fn test_parse_tolerant_build() {
+        let build = |input| parse_tolerant_build(input).unwrap().1;
 
-        let parsed = version::parse(version).unwrap();
+        assert_eq!("build/123", build("1.2.3+build/123"));
+        assert_eq!("build/123", build("  1.2.3+build/123  "));
+        assert_eq!("a+b:c_d", build("1.2.3-alpha.1+a+b:c_d"));
+        assert_eq!("build.5", build("1.2.3+build.5"));
+        assert_eq!("", build("1.2.3"));
 
-        let expected_pre = vec![
-            Identifier::AlphaNumeric(String::from("beta")),
-            Identifier::Numeric(1),
-        ];
-        assert_eq!(expected_pre, parsed.pre);
+        let (version, _) = parse_tolerant_build("1.2.3-alpha.1+build/123").unwrap();
+        assert_eq!(parse("1.2.3-alpha.1").unwrap(), version);
+        assert!(version.build.is_empty());
 
-        let expected_build = vec![Identifier::AlphaNumeric(String::from("0851523"))];
-        assert_eq!(expected_build, parsed.build);
+        assert!(parse("1.2.3+build/123").is_err());
     }
 
     #[test]
-    fn parse_metadata_overflow() {
-        let version = "0.4.0-beta.1+98765432109876543210";
+    fn test_parse_tolerant_build_errors() {
+        let tolerant = |input| parse_tolerant_build(input).map(|(version, _)| version);
+
+        assert_eq!(parse("1.2.3+"), tolerant("1.2.3+"));
+        assert_eq!(parse("1.2.3 +build"), tolerant("1.2.3 +build"));
+        assert!(tolerant("1.2+build/123").is_err());
+        assert_eq!(parse("1.2.3-01"), tolerant("1.2.3-01+build/123"));
+        assert_eq!(
+            Err(parser::Error::Lexer(::lexer::Error::UnexpectedChar('x', 16))),
+            tolerant("1.2.3+build/123 x")
+        );
+    }
 
-        let parsed = version::parse(version).unwrap();
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_parse_tolerant_build_serde() {
+        use serde_json;
 
-        let expected_pre = vec![
-            Identifier::AlphaNumeric(String::from("beta")),
-            Identifier::Numeric(1),
-        ];
-        assert_eq!(expected_pre, parsed.pre);
+        let (version, _) = parse_tolerant_build("1.2.3-alpha.1+build/123").unwrap();
+        let json = serde_json::to_string(&version).unwrap();
 
-        let expected_build = vec![
-            Identifier::AlphaNumeric(String::from("98765432109876543210")),
-        ];
-        assert_eq!(expected_build, parsed.build);
+        assert_eq!(version, serde_json::from_str::<Version>(&json).unwrap());
+        assert_eq!(version, parse(&version.to_string()).unwrap());
     }
 
     #[test]
-    fn parse_regression_01() {
-        let version = "0.0.0-WIP";
+    fn test_precedence_eq() {
+        let a = parse("1.2.3+a").unwrap();
+        let b = parse("1.2.3+b").unwrap();
+        let c = parse("1.2.3").unwrap();
+
+        assert!(a.precedence_eq(&b));
+        assert!(a.precedence_eq(&c));
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+        assert!(a.precedence_eq(&a.clone()) && a == a.clone());
+
+        assert!(!a.precedence_eq(&parse("1.2.4+a").unwrap()));
+        assert!(!a.precedence_eq(&parse("1.2.3-alpha+a").unwrap()));
+        assert!(parse("1.2.3-alpha.1+x").unwrap().precedence_eq(&parse("1.2.3-alpha.1").unwrap()));
+    }
 
-        let parsed = version::parse(version).unwrap();
+    #[test]
+    fn test_is_compatible_with() {
+        let compatible = |a: &str, b: &str| {
+            let (a, b) = (parse(a).unwrap(), parse(b).unwrap());
+            assert_eq!(a.is_compatible_with(&b), b.is_compatible_with(&a));
+            a.is_compatible_with(&b)
+        };
+
+        assert!(compatible("1.2.0", "1.5.0"));
+        assert!(compatible("1.0.0", "1.99.99"));
+        assert!(compatible("1.2.3", "1.2.3+build"));
+        assert!(compatible("1.2.3-alpha", "1.2.0"));
+        assert!(compatible("0.2.0", "0.2.7"));
+        assert!(compatible("0.0.3", "0.0.3-rc.1"));
+
+        assert!(!compatible("1.9.9", "2.0.0"));
+        assert!(!compatible("1.0.0", "0.1.0"));
+        assert!(!compatible("0.2.0", "0.3.0"));
+        assert!(!compatible("0.0.3", "0.0.4"));
+        assert!(!compatible("0.0.3", "0.1.3"));
+    }
 
-        assert_eq!(0, parsed.major);
-        assert_eq!(0, parsed.minor);
-        assert_eq!(0, parsed.patch);
+    #[test]
+    fn test_parse_illegal_character() {
+        use lexer::Error::IllegalCharacter;
+
+        assert_eq!(Err(parser::Error::Lexer(IllegalCharacter('\0', 0))), parse("\0"));
+        assert_eq!(Err(parser::Error::Lexer(IllegalCharacter('\0', 5))), parse("1.2.3\0"));
+        assert_eq!(
+            Err(parser::Error::Lexer(IllegalCharacter('\u{7f}', 6))),
+            parse("1.2.3-\u{7f}")
+        );
+        assert_eq!(
+            Err(parser::Error::Lexer(IllegalCharacter('\0', 11))),
+            parse_tolerant_build("1.2.3+build\0").map(|(version, _)| version)
+        );
+        assert_eq!(
+            Err(parser::Error::Lexer(IllegalCharacter('\u{7f}', 12))),
+            parse_tolerant_build("1.2.3+build \u{7f}").map(|(version, _)| version)
+        );
+        assert!(parse("1.2.\t3").is_err());
+    }
 
-        let expected_pre = vec![Identifier::AlphaNumeric(String::from("WIP"))];
-        assert_eq!(expected_pre, parsed.pre);
+    #[test]
+    fn test_precedence_version() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let versions = ["1.2.3+a", "1.2.3+b", "1.2.3", "1.2.3-alpha+a", "1.2.3-alpha+b", "1.2.4"];
+        let keys = || versions.iter().map(|v| PrecedenceVersion::from(parse(v).unwrap()));
+
+        let set: HashSet<_> = keys().collect();
+        assert_eq!(3, set.len());
+        assert_eq!(6, keys().map(|k| k.0).collect::<HashSet<_>>().len());
+        assert!(set.contains(&PrecedenceVersion(parse("1.2.3+c").unwrap())));
+
+        let sorted: Vec<_> = keys()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|k| k.0.to_string_no_build())
+            .collect();
+        assert_eq!(vec!["1.2.3-alpha", "1.2.3", "1.2.4"], sorted);
     }
 }